@@ -1,64 +1,351 @@
 use crate::app::states;
+use crate::difficulty::Index;
+use crate::generator::Generator;
+use crate::history::{History, Logs};
+use crate::lessons::{Curriculum, Progress};
+use crate::race::{Client, Host};
+use crate::session::{Ghost, GhostRun, KeystrokeLog, Settings, Start};
+use crate::text_model::{self, Data, Format, ImportOptions, Imported, ParseError};
+use crate::typ::{Interpreter, Program};
+use crate::widgets::TooSmall;
 
-use crossterm::{
-    event::{self, DisableMouseCapture},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
+use super::{Event, EventSource, TerminalEvents};
+use super::error::{read_file, AppError};
+use super::terminal::{SharedWriter, TerminalGuard};
 
-use tui::{backend::CrosstermBackend, Terminal};
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::io;
+use std::path::{Path, PathBuf};
+use tui::{
+    backend::{Backend, CrosstermBackend},
+    Terminal,
+};
 
-pub struct App<W>
+pub struct App<B>
 where
-    W: std::io::Write,
+    B: Backend,
 {
-    state: std::boxed::Box<dyn states::State<CrosstermBackend<W>>>,
+    state: std::boxed::Box<dyn states::State<B>>,
+    keystroke_log_path: Option<PathBuf>,
 }
-pub type AppError = std::boxed::Box<dyn std::error::Error>;
+/// Seconds the host counts down from when a race starts, unless told otherwise.
+const DEFAULT_RACE_COUNTDOWN: u64 = 3;
 
-impl<W> App<W>
+impl<B> App<B>
 where
-    W: std::io::Write,
+    B: Backend,
 {
-    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<App<W>, AppError> {
-        let file_content = std::str::from_utf8(&std::fs::read(path)?)?.to_string();
-        Ok(App::<W> {
-            state: std::boxed::Box::new(states::Typing::new(&file_content)?),
+    /// An app for a session on the text at `path`, or on stdin if `path`
+    /// is `-`, read as `options` say.
+    pub fn from_file(
+        path: &str,
+        options: &ImportOptions,
+        settings: Settings,
+    ) -> Result<App<B>, AppError> {
+        let file_content = read_text(path, options, settings.seed)?;
+        App::new(&file_content, settings).map_err(|e| e.in_file(source_name(path)))
+    }
+
+    /// An app for a session on the klata text in `file_content`.
+    pub fn new(file_content: &str, settings: Settings) -> Result<App<B>, AppError> {
+        let start = settings.start;
+        let ghost_run = settings.ghost.clone();
+        let mut typing = states::Typing::new(file_content, settings)?;
+        if let Some(ghost_run) = ghost_run {
+            if let Some(ghost) = find_ghost(&ghost_run, &typing.text_id())? {
+                typing.set_ghost(ghost);
+            }
+        }
+        Ok(App::<B> {
+            state: match start {
+                Start::FirstKeystroke => std::boxed::Box::new(typing),
+                Start::Countdown(seconds) => {
+                    std::boxed::Box::new(states::Countdown::new(typing, seconds))
+                }
+            },
+            keystroke_log_path: None,
         })
     }
 
-    pub fn run(mut self, buffer: W) -> Result<(), AppError> {
-        let mut terminal = create_terminal(buffer)?;
+    /// An endless session on text from `generator`.
+    pub fn generate(mut generator: Generator, settings: Settings) -> Result<App<B>, AppError> {
+        let start = settings.start;
+        let first = Data {
+            text: generator.generate(1),
+            ..Data::default()
+        };
+        let mut typing = states::Typing::new(&first.to_xml(), settings)?;
+        typing.set_generator(generator);
+        Ok(App::<B> {
+            state: match start {
+                Start::FirstKeystroke => std::boxed::Box::new(typing),
+                Start::Countdown(seconds) => {
+                    std::boxed::Box::new(states::Countdown::new(typing, seconds))
+                }
+            },
+            keystroke_log_path: None,
+        })
+    }
+
+    /// Hosts a race on `port` over the text at `path`, waiting in the lobby
+    /// for others to join.
+    pub fn serve(
+        path: &str,
+        options: &ImportOptions,
+        port: u16,
+        name: &str,
+        settings: Settings,
+    ) -> Result<App<B>, AppError> {
+        let file_content = read_text(path, options, settings.seed)?;
+        let countdown = match settings.start {
+            Start::Countdown(seconds) => seconds,
+            Start::FirstKeystroke => DEFAULT_RACE_COUNTDOWN,
+        };
+        let ghost_run = settings.ghost.clone();
+        let mut typing = states::Typing::new(&file_content, settings)
+            .map_err(|e| AppError::from(e).in_file(source_name(path)))?;
+        if let Some(ghost_run) = ghost_run {
+            if let Some(ghost) = find_ghost(&ghost_run, &typing.text_id())? {
+                typing.set_ghost(ghost);
+            }
+        }
+        let host = Host::bind(("0.0.0.0", port), name, &file_content)?;
+        let address = host.address();
+        typing.set_link(std::boxed::Box::new(host));
+        Ok(App::<B> {
+            state: std::boxed::Box::new(
+                states::Lobby::new(typing, name, countdown).with_address(address),
+            ),
+            keystroke_log_path: None,
+        })
+    }
+
+    /// Joins the race hosted at `address`, on whatever text the host chose.
+    pub fn join(address: &str, name: &str, settings: Settings) -> Result<App<B>, AppError> {
+        let client = Client::connect(address, name)?;
+        let ghost_run = settings.ghost.clone();
+        let mut typing = states::Typing::new(client.source(), settings)?;
+        if let Some(ghost_run) = ghost_run {
+            if let Some(ghost) = find_ghost(&ghost_run, &typing.text_id())? {
+                typing.set_ghost(ghost);
+            }
+        }
+        typing.set_link(std::boxed::Box::new(client));
+        Ok(App::<B> {
+            state: std::boxed::Box::new(states::Lobby::new(typing, name, DEFAULT_RACE_COUNTDOWN)),
+            keystroke_log_path: None,
+        })
+    }
+
+    pub fn replay<P: AsRef<Path>>(path: P, speed: f64) -> Result<App<B>, AppError> {
+        Ok(App::<B> {
+            state: std::boxed::Box::new(states::Replay::new(load_log(path)?, speed)?),
+            keystroke_log_path: None,
+        })
+    }
+
+    /// Works through the GNU Typist lesson script at `path`, from `label`
+    /// if one is given.
+    pub fn tutorial<P: AsRef<Path>>(
+        path: P,
+        label: Option<&str>,
+        settings: Settings,
+    ) -> Result<App<B>, AppError>
+    where
+        B: 'static,
+    {
+        let source = read_file(&path)?;
+        let program = Program::parse(&source).map_err(|e| AppError::from(e.in_file(&path)))?;
+        let mut interpreter = Interpreter::new(program);
+        if let Some(label) = label {
+            interpreter = interpreter
+                .start_at(label)
+                .ok_or_else(|| AppError::from(format!("No label '{}'", label).as_str()))?;
+        }
+        Ok(App::<B> {
+            state: std::boxed::Box::new(states::Tutorial::new(interpreter, settings)),
+            keystroke_log_path: None,
+        })
+    }
+
+    /// Shows the lessons of the curriculum at `path`, or the built-in one,
+    /// with the progress of `profile`.
+    pub fn lessons(
+        path: Option<&str>,
+        profile: &str,
+        settings: Settings,
+    ) -> Result<App<B>, AppError>
+    where
+        B: 'static,
+    {
+        let curriculum = match path {
+            Some(path) => Curriculum::load(path).map_err(|e| AppError::reading(path, e))?,
+            None => Curriculum::default(),
+        };
+        if profile.is_empty() || !profile.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+            return Err("Profile names may only have letters, digits, - and _".into());
+        }
+        let map = match Progress::user_path(profile, &curriculum) {
+            Some(progress_path) => {
+                let progress = Progress::load(&progress_path)
+                    .map_err(|e| AppError::reading(&progress_path, e))?;
+                states::LessonMap::new(curriculum, progress, settings).saving_to(progress_path)
+            }
+            None => states::LessonMap::new(curriculum, Progress::default(), settings),
+        };
+        Ok(App::<B> {
+            state: std::boxed::Box::new(map),
+            keystroke_log_path: None,
+        })
+    }
+
+    /// Saves the keystroke log of the session to `path` when the app exits.
+    pub fn record_keystrokes<P: AsRef<Path>>(mut self, path: P) -> App<B> {
+        self.keystroke_log_path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Draws to `terminal` and handles events from `events` until the app
+    /// is done or the events run out.
+    pub fn drive<E: EventSource>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+        events: &mut E,
+    ) -> Result<(), AppError> {
         loop {
-            terminal.draw(|f| self.state.ui(f))?;
-            self.state = self.state.handle_event(event::read()?);
-            if self.state.terminate() {
+            terminal
+                .draw(|f| draw(self.state.as_ref(), f))
+                .map_err(AppError::Terminal)?;
+            let event = events
+                .next_event(self.state.tick_rate())
+                .map_err(AppError::Terminal)?;
+            self.state = match event {
+                Some(Event::Tick) => self.state.tick(),
+                Some(Event::Resize(..)) => {
+                    // reflows on the next draw
+                    terminal.autoresize().map_err(AppError::Terminal)?;
+                    continue;
+                }
+                Some(event) => self.state.handle_event(event),
+                None => break,
+            };
+            if self.is_done() {
                 break;
             }
         }
-        teardown_terminal(terminal)?;
+        Ok(())
+    }
+
+    /// Whether the user has finished with the app.
+    pub fn is_done(&self) -> bool {
+        self.state.terminate()
+    }
+}
+
+impl<W> App<CrosstermBackend<SharedWriter<W>>>
+where
+    W: std::io::Write + Send + 'static,
+{
+    pub fn run(mut self, buffer: W) -> Result<(), AppError> {
+        let mut terminal = TerminalGuard::new(buffer).map_err(AppError::Terminal)?;
+        self.drive(&mut terminal, &mut TerminalEvents)?;
+        terminal.restore().map_err(AppError::Terminal)?;
+        if let (Some(log), Some(path)) = (self.state.keystroke_log(), &self.keystroke_log_path) {
+            log.save(path)?;
+        }
+        if let Some(history) = History::user() {
+            for (record, log) in self.state.records() {
+                history.append(&record)?;
+                if let (Some(log), Some(logs)) = (log, Logs::user()) {
+                    logs.save(log)?;
+                }
+            }
+        }
         Ok(())
     }
 }
 
-fn create_terminal<W: std::io::Write>(
-    mut buffer: W,
-) -> Result<Terminal<CrosstermBackend<W>>, std::io::Error> {
-    enable_raw_mode()?;
-    execute!(buffer, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(buffer);
-    Ok(Terminal::new(backend)?)
+/// Draws the state, or says so if the terminal is too small for it.
+fn draw<B: Backend>(state: &dyn states::State<B>, frame: &mut tui::Frame<B>) {
+    let (width, height) = state.min_size();
+    let size = frame.size();
+    if size.width < width || size.height < height {
+        frame.render_widget(TooSmall::new(width, height), size);
+    } else {
+        state.ui(frame);
+    }
 }
 
-fn teardown_terminal<W: std::io::Write>(
-    mut terminal: Terminal<CrosstermBackend<W>>,
-) -> Result<(), std::io::Error> {
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
-    Ok(())
+/// Reads the text at `path`, or stdin if `path` is `-`, as a klata text
+/// file, picking one at random from `seed` if there are several.
+pub fn read_text(path: &str, options: &ImportOptions, seed: u64) -> Result<String, AppError> {
+    let imported = import_texts(path, options)?;
+    match imported.texts.choose(&mut ChaCha8Rng::seed_from_u64(seed)) {
+        Some(data) => Ok(data.to_xml()),
+        None => match imported.problems.into_iter().next() {
+            Some(problem) => Err(problem.into()),
+            None if options.difficulty.is_set() => {
+                let message = format!("No texts of that difficulty in {}", source_name(path));
+                Err(message.as_str().into())
+            }
+            None => Err(format!("No texts in {}", source_name(path)).as_str().into()),
+        },
+    }
+}
+
+/// Every text in the file at `path`, or stdin if `path` is `-`, with
+/// problems in the entries left out, as are texts outside the bounds on
+/// difficulty.
+pub fn import_texts(path: &str, options: &ImportOptions) -> Result<Imported, AppError> {
+    let content = match path {
+        "-" => io::read_to_string(io::stdin())?,
+        path => read_file(path)?,
+    };
+    let format = options.format.unwrap_or_else(|| Format::detect(path, &content));
+    let in_file = |e: ParseError| e.in_file(source_name(path));
+    let mut imported = text_model::import(&content, format, &options.fields)
+        .map_err(|e| AppError::from(in_file(e)))?;
+    imported.problems = imported.problems.into_iter().map(in_file).collect();
+    if options.difficulty.is_set() {
+        // scores are cached in the user's index, if they have somewhere to keep it
+        let path = Index::user_path();
+        let mut index = match &path {
+            Some(path) => Index::load(path).map_err(|e| AppError::reading(path, e))?,
+            None => Index::default(),
+        };
+        options.difficulty.retain(&mut imported.texts, &mut index);
+        if let Some(path) = path {
+            index.save(path)?;
+        }
+    }
+    Ok(imported)
+}
+
+/// What to call the text at `path` in errors.
+fn source_name(path: &str) -> &str {
+    match path {
+        "-" => "<stdin>",
+        path => path,
+    }
+}
+
+fn load_log<P: AsRef<Path>>(path: P) -> Result<KeystrokeLog, AppError> {
+    KeystrokeLog::load(&path).map_err(|e| AppError::reading(path, e))
+}
+
+fn find_ghost(ghost_run: &GhostRun, text_id: &str) -> Result<Option<Ghost>, AppError> {
+    let log = match ghost_run {
+        GhostRun::Best => match Logs::user() {
+            Some(logs) => logs.best(text_id)?,
+            None => None,
+        },
+        GhostRun::File(path) => Some(load_log(path)?),
+    };
+    match log {
+        Some(log) if log.text_model()?.id() != text_id => {
+            Err("The ghost's keystroke log was recorded against a different text".into())
+        }
+        log => Ok(log.as_ref().map(Ghost::new)),
+    }
 }
@@ -0,0 +1,118 @@
+use crate::text_model::{DataError, ParseError};
+use std::{
+    error::Error,
+    fmt, io,
+    path::{Path, PathBuf},
+};
+
+/// Everything that can stop the app, each with its own exit code.
+#[derive(Debug)]
+pub enum AppError {
+    /// A file the app was asked to read does not exist.
+    MissingFile(PathBuf),
+    /// A text file or lesson script is malformed, whatever its format:
+    /// klata XML, Markdown, plain text, JSON, CSV or gtypist.
+    BadText(ParseError),
+    /// A text contains a character klata cannot type.
+    UnsupportedCharacter(ParseError),
+    /// Validation found this many problems with texts.
+    InvalidTexts(usize),
+    /// The terminal could not be set up, drawn on or restored.
+    Terminal(io::Error),
+    Io(io::Error),
+    Other(Box<dyn Error>),
+}
+
+impl AppError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            AppError::Io(_) | AppError::Other(_) => 1,
+            AppError::MissingFile(_) => 3,
+            AppError::BadText(_) => 4,
+            AppError::UnsupportedCharacter(_) => 5,
+            AppError::Terminal(_) => 6,
+            AppError::InvalidTexts(_) => 7,
+        }
+    }
+
+    /// Names the file a problem with a text was found in.
+    pub fn in_file<P: AsRef<Path>>(self, path: P) -> AppError {
+        match self {
+            AppError::BadText(e) => AppError::BadText(e.in_file(path)),
+            AppError::UnsupportedCharacter(e) => AppError::UnsupportedCharacter(e.in_file(path)),
+            e => e,
+        }
+    }
+
+    /// The error from reading the file at `path`, naming the file if it
+    /// is missing.
+    pub fn reading<P: AsRef<Path>>(path: P, e: Box<dyn Error>) -> AppError {
+        match e.downcast_ref::<io::Error>() {
+            Some(io) if io.kind() == io::ErrorKind::NotFound => {
+                AppError::MissingFile(path.as_ref().to_path_buf())
+            }
+            _ => e.into(),
+        }
+    }
+}
+
+/// Reads the whole of the text file at `path`.
+pub fn read_file<P: AsRef<Path>>(path: P) -> Result<String, AppError> {
+    std::fs::read_to_string(&path).map_err(|e| AppError::reading(path, e.into()))
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AppError::MissingFile(path) => write!(f, "No such file: {}", path.display()),
+            AppError::BadText(e) | AppError::UnsupportedCharacter(e) => write!(f, "{}", e),
+            AppError::InvalidTexts(1) => write!(f, "Found a problem"),
+            AppError::InvalidTexts(count) => write!(f, "Found {} problems", count),
+            AppError::Terminal(e) => write!(f, "Could not use the terminal: {}", e),
+            AppError::Io(e) => write!(f, "{}", e),
+            AppError::Other(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for AppError {}
+
+impl From<ParseError> for AppError {
+    fn from(e: ParseError) -> AppError {
+        match e.error() {
+            DataError::UnsupportedCharacter(_) => AppError::UnsupportedCharacter(e),
+            _ => AppError::BadText(e),
+        }
+    }
+}
+
+impl From<io::Error> for AppError {
+    fn from(e: io::Error) -> AppError {
+        AppError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(e: serde_json::Error) -> AppError {
+        AppError::Other(e.into())
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> AppError {
+        AppError::Other(message.into())
+    }
+}
+
+impl From<Box<dyn Error>> for AppError {
+    fn from(e: Box<dyn Error>) -> AppError {
+        let e = match e.downcast::<ParseError>() {
+            Ok(data) => return (*data).into(),
+            Err(e) => e,
+        };
+        match e.downcast::<io::Error>() {
+            Ok(io) => AppError::Io(*io),
+            Err(e) => AppError::Other(e),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use crossterm::event::{self, KeyCode, KeyEvent, KeyModifiers};
+use std::{io, time::Duration};
+
+/// A key as the app sees it, whatever terminal it came from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum KeyPress {
+    Char(char),
+    /// A character typed with control held down.
+    Ctrl(char),
+    Backspace,
+    Enter,
+    Tab,
+    Esc,
+    Up,
+    Down,
+    /// Any other key, which the app ignores.
+    Other,
+}
+
+impl From<KeyEvent> for KeyPress {
+    fn from(key: KeyEvent) -> KeyPress {
+        match key.code {
+            KeyCode::Char(c) if key.modifiers.contains(KeyModifiers::CONTROL) => KeyPress::Ctrl(c),
+            KeyCode::Char(c) => KeyPress::Char(c),
+            KeyCode::Backspace => KeyPress::Backspace,
+            KeyCode::Enter => KeyPress::Enter,
+            KeyCode::Tab => KeyPress::Tab,
+            KeyCode::Esc => KeyPress::Esc,
+            KeyCode::Up => KeyPress::Up,
+            KeyCode::Down => KeyPress::Down,
+            _ => KeyPress::Other,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event {
+    Key(KeyPress),
+    /// The terminal now has this many columns and rows.
+    Resize(u16, u16),
+    /// Nothing happened for a while.
+    Tick,
+}
+
+impl Event {
+    /// The app's view of a terminal event, if it cares about it at all.
+    pub fn from_terminal(event: event::Event) -> Option<Event> {
+        match event {
+            event::Event::Key(key) => Some(Event::Key(key.into())),
+            event::Event::Resize(columns, rows) => Some(Event::Resize(columns, rows)),
+            _ => None,
+        }
+    }
+}
+
+/// Where an app's events come from.
+pub trait EventSource {
+    /// The next event, or `Event::Tick` if none arrives within `tick_rate`.
+    /// `None` once there will be no more events.
+    fn next_event(&mut self, tick_rate: Duration) -> io::Result<Option<Event>>;
+}
+
+/// Events read from the terminal.
+pub struct TerminalEvents;
+
+impl EventSource for TerminalEvents {
+    fn next_event(&mut self, tick_rate: Duration) -> io::Result<Option<Event>> {
+        if !event::poll(tick_rate)? {
+            return Ok(Some(Event::Tick));
+        }
+        Ok(Some(
+            Event::from_terminal(event::read()?).unwrap_or(Event::Tick),
+        ))
+    }
+}
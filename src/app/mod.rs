@@ -1,4 +1,15 @@
+#[cfg(test)]
+mod tests;
+
+#[allow(clippy::module_inception)]
 mod app;
+mod error;
+mod event;
+mod script;
 mod states;
+mod terminal;
 
 pub use app::*;
+pub use error::{read_file, AppError};
+pub use event::{Event, EventSource, KeyPress, TerminalEvents};
+pub use script::{run_script, Script};
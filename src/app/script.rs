@@ -0,0 +1,103 @@
+use super::states::Typing;
+use super::KeyPress;
+use crate::history::Record;
+use crate::session::Settings;
+use std::{
+    boxed::Box,
+    error::Error,
+    time::{Duration, Instant},
+};
+
+/// The gap between keys which have no timestamp of their own.
+pub const DEFAULT_STEP: Duration = Duration::from_millis(200);
+
+/// A key in a script, and when to press it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Step {
+    /// Time since the start of the script.
+    pub at: Duration,
+    pub key: KeyPress,
+}
+
+/// Keystrokes to type into a session without a terminal.
+///
+/// Characters are typed as they appear, except for line breaks, which are
+/// ignored so long scripts can be wrapped. Special keys are written in angle
+/// brackets: `<backspace>` (or `<bs>`), `<enter>`, `<tab>`, `<esc>`,
+/// `<ctrl-p>` and `<lt>` for a literal `<`. `<@MS>` presses the next key MS
+/// milliseconds after the start. Other keys follow the one before by
+/// `DEFAULT_STEP`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Script {
+    pub steps: Vec<Step>,
+}
+
+impl Script {
+    pub fn parse(s: &str) -> Result<Script, Box<dyn Error>> {
+        let mut steps = Vec::new();
+        let mut next_at = None;
+        let mut at = Duration::ZERO;
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            let key = match c {
+                '\n' | '\r' => continue,
+                '<' => {
+                    let name = chars.by_ref().take_while(|&c| c != '>').collect::<String>();
+                    if let Some(ms) = name.strip_prefix('@') {
+                        let ms = ms
+                            .parse()
+                            .map_err(|_| format!("Bad timestamp in script: <{}>", name))?;
+                        next_at = Some(Duration::from_millis(ms));
+                        continue;
+                    }
+                    special_key(&name).ok_or(format!("Unknown key in script: <{}>", name))?
+                }
+                c => KeyPress::Char(c),
+            };
+            at = match next_at.take() {
+                Some(next_at) => next_at,
+                None if steps.is_empty() => Duration::ZERO,
+                None => at + DEFAULT_STEP,
+            };
+            steps.push(Step { at, key });
+        }
+        Ok(Script { steps })
+    }
+}
+
+fn special_key(name: &str) -> Option<KeyPress> {
+    Some(match name {
+        "backspace" | "bs" => KeyPress::Backspace,
+        "enter" => KeyPress::Enter,
+        "tab" => KeyPress::Tab,
+        "esc" => KeyPress::Esc,
+        "lt" => KeyPress::Char('<'),
+        "ctrl-p" => KeyPress::Ctrl('p'),
+        _ => return None,
+    })
+}
+
+/// Types `script` into a session on the text in `text_file_content`, just
+/// as if it had been typed at the terminal, and returns the results. A
+/// session still going when the script runs out is finished as if by
+/// escape.
+pub fn run_script(
+    text_file_content: &str,
+    settings: Settings,
+    script: &Script,
+) -> Result<Record, Box<dyn Error>> {
+    let mut typing = Typing::new(text_file_content, settings)?;
+    let start = Instant::now();
+    let mut now = start;
+    for step in &script.steps {
+        now = start + step.at;
+        let finished = match typing.advance(now) {
+            Some(finished) => Some(finished),
+            None => typing.key(step.key, now),
+        };
+        if let Some(finished) = finished {
+            return Ok(finished.result());
+        }
+    }
+    Ok(typing.finish(now).result())
+}
@@ -0,0 +1,68 @@
+use super::{State, Typing};
+use crate::app::{Event, KeyPress};
+use crate::widgets::Popup;
+use std::{
+    boxed::Box,
+    time::{Duration, Instant},
+};
+
+/// Counts down to the start of a typing session.
+#[derive(Default)]
+pub struct Countdown {
+    typing: Typing,
+    end: Option<Instant>,
+}
+
+impl Countdown {
+    pub fn new(typing: Typing, seconds: u64) -> Countdown {
+        Countdown {
+            typing,
+            end: Some(Instant::now() + Duration::from_secs(seconds)),
+        }
+    }
+    fn take(&mut self) -> Countdown {
+        std::mem::take(self)
+    }
+    fn remaining(&self, now: Instant) -> Duration {
+        self.end
+            .map_or(Duration::ZERO, |end| end.saturating_duration_since(now))
+    }
+}
+
+impl<B> State<B> for Countdown
+where
+    B: tui::backend::Backend,
+{
+    fn handle_event(&mut self, event: Event) -> Box<dyn State<B>> {
+        let now = Instant::now();
+        match event {
+            // ticks only come between keys, so a key may be the first
+            // thing after the countdown ends
+            _ if self.remaining(now).is_zero() => {
+                let mut typing = std::mem::take(&mut self.typing);
+                typing.start(now);
+                typing.handle_event(event)
+            }
+            Event::Key(KeyPress::Esc) => Box::new(self.typing.finish(now)),
+            _ => Box::new(self.take()),
+        }
+    }
+    fn tick(&mut self) -> Box<dyn State<B>> {
+        let now = Instant::now();
+        if self.remaining(now).is_zero() {
+            let mut typing = std::mem::take(&mut self.typing);
+            typing.start(now);
+            return Box::new(typing);
+        }
+        Box::new(self.take())
+    }
+    fn min_size(&self) -> (u16, u16) {
+        State::<B>::min_size(&self.typing)
+    }
+    fn ui(&self, frame: &mut tui::Frame<B>) {
+        State::<B>::ui(&self.typing, frame);
+        let remaining = self.remaining(Instant::now());
+        let seconds = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+        frame.render_widget(Popup::new(seconds.max(1).to_string()), frame.size());
+    }
+}
@@ -1,15 +1,198 @@
-use super::State;
-use std::boxed::Box;
-pub struct Finished;
+use super::{State, MIN_WIDTH};
+use crate::app::Event;
+use crate::history::Record;
+use crate::race::{LaneInfo, Link, Standing};
+use crate::session::{Analysis, Failure, KeystrokeLog, Stats};
+use crate::text_model::{Correction, TextModel};
+use crate::widgets::TextArea;
+use std::{boxed::Box, time::Duration};
+use tui::{
+    layout::{Constraint, Direction, Layout},
+    widgets::{Block, BorderType, Borders, Paragraph},
+};
+
+#[derive(Default)]
+pub struct Finished {
+    text_model: Option<TextModel>,
+    stats: Stats,
+    failure: Option<Failure>,
+    log: KeystrokeLog,
+    standings: Vec<Standing>,
+    seed: Option<u64>,
+    link: Option<Box<dyn Link>>,
+    lane: LaneInfo,
+    analysis: Option<Analysis>,
+    difficulty: Option<f64>,
+    done: bool,
+}
+
+impl Finished {
+    pub fn new(
+        text_model: TextModel,
+        stats: Stats,
+        failure: Option<Failure>,
+        log: KeystrokeLog,
+    ) -> Finished {
+        Finished {
+            text_model: Some(text_model),
+            stats,
+            failure,
+            log,
+            standings: Vec::new(),
+            seed: None,
+            link: None,
+            lane: LaneInfo::default(),
+            analysis: None,
+            difficulty: None,
+            done: false,
+        }
+    }
+    /// Adds the places of the local racers, and the seed for any bots.
+    pub fn with_race(mut self, standings: Vec<Standing>, seed: Option<u64>) -> Finished {
+        self.standings = standings;
+        self.seed = seed;
+        self
+    }
+    /// Keeps sharing our final `lane` with a networked race, and follows
+    /// the other racers until they finish.
+    pub fn with_link(mut self, link: Box<dyn Link>, lane: LaneInfo) -> Finished {
+        self.link = Some(link);
+        self.lane = lane;
+        self
+    }
+    /// Adds how each finger and hand fared.
+    pub fn with_analysis(mut self, analysis: Analysis) -> Finished {
+        self.analysis = Some(analysis);
+        self
+    }
+    /// Adds the difficulty of the text.
+    pub fn with_difficulty(mut self, difficulty: f64) -> Finished {
+        self.difficulty = Some(difficulty);
+        self
+    }
+    /// The results of the session, whether or not anything was typed.
+    pub fn result(&self) -> Record {
+        Record {
+            analysis: self.analysis.clone(),
+            difficulty: self.difficulty,
+            ..Record::new(
+                &self.stats,
+                self.text_model.as_ref().unwrap(),
+                self.failure.as_ref(),
+            )
+        }
+    }
+    /// The height of the results box, with its borders.
+    fn summary_height(&self) -> u16 {
+        self.summary().lines().count() as u16 + 2
+    }
+    fn take(&mut self) -> Finished {
+        std::mem::take(self)
+    }
+    fn summary(&self) -> String {
+        let mut lines = Vec::new();
+        if let Some(failure) = &self.failure {
+            lines.push(format!("Failed: {}", failure));
+        }
+        lines.extend([
+            format!("WPM: {:.1}", self.stats.wpm()),
+            format!("Accuracy: {:.1}%", self.stats.accuracy() * 100.0),
+            format!("Errors: {}", self.stats.errors),
+            format!("Time: {:.1}s", self.stats.elapsed.as_secs_f64()),
+        ]);
+        let correction = self.text_model.as_ref().unwrap().correction();
+        if correction != Correction::Free {
+            lines.push(format!("Correction: {}", correction));
+        }
+        if let Some(difficulty) = self.difficulty {
+            lines.push(format!("Difficulty: {:.2}", difficulty));
+        }
+        if !self.stats.idle.is_zero() {
+            lines.push(format!("Paused: {:.1}s", self.stats.idle.as_secs_f64()));
+        }
+        let analysis = self.analysis.as_ref().map(Analysis::summary).unwrap_or_default();
+        if !analysis.is_empty() {
+            lines.push(String::new());
+            lines.extend(analysis);
+        }
+        if !self.standings.is_empty() {
+            let others = self.link.as_ref().map(|l| l.others()).unwrap_or_default();
+            let mut standings = self.standings.clone();
+            standings.extend(others.iter().filter(|lane| lane.done).map(|lane| Standing {
+                name: lane.name.clone(),
+                time: lane.time_ms.map(Duration::from_millis),
+            }));
+            lines.push(String::new());
+            lines.push(match self.seed {
+                Some(seed) => format!("Race (seed {}):", seed),
+                None => "Race:".to_string(),
+            });
+            lines.extend(
+                Standing::rank(standings)
+                    .iter()
+                    .enumerate()
+                    .map(|(i, standing)| format!("{}. {}", i + 1, standing)),
+            );
+            let racing = others.iter().filter(|lane| !lane.done).count();
+            if self.link.as_ref().is_some_and(|link| link.disconnected()) {
+                lines.push("Host disconnected".to_string());
+            } else if racing > 0 {
+                lines.push(format!("Waiting for {} more to finish", racing));
+            }
+        }
+        lines.join("\n")
+    }
+}
 
 impl<B> super::State<B> for Finished
 where
     B: tui::backend::Backend,
 {
-    fn handle_event(&mut self, _event: crossterm::event::Event) -> Box<dyn State<B>> {
-        Box::new(Finished)
+    fn handle_event(&mut self, event: Event) -> Box<dyn State<B>> {
+        if let Event::Key(_) = event {
+            self.done = true;
+        }
+        Box::new(self.take())
+    }
+    fn tick(&mut self) -> Box<dyn State<B>> {
+        if let Some(link) = &mut self.link {
+            link.update(self.lane.clone());
+        }
+        Box::new(self.take())
     }
     fn terminate(&self) -> bool {
-        true
+        self.done
+    }
+    fn min_size(&self) -> (u16, u16) {
+        (MIN_WIDTH, 3 + self.summary_height())
+    }
+    fn ui(&self, frame: &mut tui::Frame<B>) {
+        let summary = self.summary();
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(3),
+                Constraint::Length(self.summary_height()),
+            ])
+            .split(frame.size());
+        frame.render_widget(TextArea::new(self.text_model.as_ref().unwrap()), chunks[0]);
+        frame.render_widget(
+            Paragraph::new(summary).block(
+                Block::default()
+                    .title(" Results - press any key to exit ")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded),
+            ),
+            chunks[1],
+        );
+    }
+    fn record(&self) -> Option<Record> {
+        if self.stats.typed == 0 {
+            return None;
+        }
+        Some(self.result())
+    }
+    fn keystroke_log(&self) -> Option<&KeystrokeLog> {
+        Some(&self.log)
     }
 }
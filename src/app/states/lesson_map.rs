@@ -0,0 +1,261 @@
+use super::{State, Typing, MIN_WIDTH};
+use crate::app::{Event, KeyPress};
+use crate::history::Record;
+use crate::lessons::{drill, Curriculum, Progress};
+use crate::session::{KeystrokeLog, Rules, Settings};
+use crate::text_model::Data;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::{boxed::Box, path::PathBuf, time::Duration};
+use tui::{
+    layout::{Constraint, Direction, Layout},
+    widgets::{Block, BorderType, Borders, Paragraph},
+};
+
+/// Lists the lessons of a curriculum, showing which are unlocked and the
+/// best run through each, and drills whichever the typist chooses.
+pub struct LessonMap<B> {
+    curriculum: Curriculum,
+    progress: Progress,
+    /// Where progress is saved after each lesson, if anywhere.
+    path: Option<PathBuf>,
+    settings: Settings,
+    rng: ChaCha8Rng,
+    selected: usize,
+    /// The lesson being typed, until its results are dismissed.
+    session: Option<Box<dyn State<B>>>,
+    /// How the last lesson went.
+    message: String,
+    done: bool,
+    /// The results of every lesson typed, for the history.
+    finished: Vec<(Record, Option<KeystrokeLog>)>,
+}
+
+impl<B> Default for LessonMap<B> {
+    fn default() -> LessonMap<B> {
+        LessonMap {
+            curriculum: Curriculum::default(),
+            progress: Progress::default(),
+            path: None,
+            settings: Settings::default(),
+            rng: ChaCha8Rng::seed_from_u64(0),
+            selected: 0,
+            session: None,
+            message: String::new(),
+            done: false,
+            finished: Vec::new(),
+        }
+    }
+}
+
+impl<B> LessonMap<B>
+where
+    B: tui::backend::Backend + 'static,
+{
+    /// Starts at the furthest unlocked lesson. Drills are typed with
+    /// `settings`, and generated from its seed.
+    pub fn new(curriculum: Curriculum, progress: Progress, settings: Settings) -> LessonMap<B> {
+        LessonMap {
+            selected: progress.unlocked.clamp(1, curriculum.lessons.len()) - 1,
+            rng: ChaCha8Rng::seed_from_u64(settings.seed),
+            curriculum,
+            progress,
+            settings,
+            ..LessonMap::default()
+        }
+    }
+    /// Saves progress to `path` after each lesson.
+    pub fn saving_to(mut self, path: PathBuf) -> LessonMap<B> {
+        self.path = Some(path);
+        self
+    }
+    fn take(&mut self) -> LessonMap<B> {
+        std::mem::take(self)
+    }
+    fn start(&mut self) {
+        if !self.progress.is_unlocked(self.selected) {
+            self.message = "That lesson is still locked".to_string();
+            return;
+        }
+        let data = Data {
+            text: drill(&self.curriculum, self.selected, &mut self.rng),
+            ..Data::default()
+        };
+        let settings = Settings {
+            // lessons are judged once they are over
+            rules: Rules::default(),
+            ..self.settings.clone()
+        };
+        // drills only use the curriculum's keys, which are checked on load
+        match Typing::new(&data.to_xml(), settings) {
+            Ok(mut typing) => {
+                typing.set_title(&self.curriculum.lessons[self.selected].name);
+                self.session = Some(Box::new(typing));
+                self.message.clear();
+            }
+            Err(e) => self.message = e.to_string(),
+        }
+    }
+    /// Keeps the result of a finished session for the history.
+    fn keep(&mut self, session: &dyn State<B>) -> Option<Record> {
+        let record = session.record()?;
+        let log = session.keystroke_log().cloned();
+        self.finished.push((record.clone(), log));
+        Some(record)
+    }
+    /// Records the lesson once its session is over.
+    fn check_session(&mut self) {
+        if !self
+            .session
+            .as_ref()
+            .is_some_and(|session| session.terminate())
+        {
+            return;
+        }
+        let record = match self
+            .session
+            .take()
+            .and_then(|session| self.keep(session.as_ref()))
+        {
+            Some(record) if record.completed => record,
+            _ => return,
+        };
+        let lesson = &self.curriculum.lessons[self.selected];
+        let was_unlocked = self.progress.unlocked;
+        self.message = if self
+            .progress
+            .record(&self.curriculum, self.selected, &record)
+        {
+            match self.curriculum.lessons.get(self.selected + 1) {
+                Some(next) if self.progress.unlocked > was_unlocked => {
+                    self.selected += 1;
+                    format!("Passed! {} is unlocked", next.name)
+                }
+                _ => "Passed!".to_string(),
+            }
+        } else {
+            format!(
+                "Not yet: {} needs {:.0}% accuracy at {:.0} WPM",
+                lesson.name,
+                lesson.min_accuracy * 100.0,
+                lesson.min_wpm
+            )
+        };
+        if let Some(path) = &self.path {
+            if let Err(e) = self.progress.save(path) {
+                self.message = format!("Could not save progress: {}", e);
+            }
+        }
+    }
+    fn lines(&self) -> String {
+        self.curriculum
+            .lessons
+            .iter()
+            .enumerate()
+            .map(|(i, lesson)| {
+                let status = match (
+                    self.progress.is_unlocked(i),
+                    self.progress.best.get(&lesson.name),
+                ) {
+                    (false, _) => "locked".to_string(),
+                    (true, Some(best)) => {
+                        format!("best {:.0} WPM, {:.0}%", best.wpm, best.accuracy * 100.0)
+                    }
+                    (true, None) => String::new(),
+                };
+                let marker = if i == self.selected { '>' } else { ' ' };
+                format!(
+                    "{} {}. {:<12} {:<12} {}",
+                    marker,
+                    i + 1,
+                    lesson.name,
+                    lesson.keys,
+                    status
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl<B> State<B> for LessonMap<B>
+where
+    B: tui::backend::Backend + 'static,
+{
+    fn handle_event(&mut self, event: Event) -> Box<dyn State<B>> {
+        if let Some(session) = &mut self.session {
+            self.session = Some(session.handle_event(event));
+            self.check_session();
+            return Box::new(self.take());
+        }
+        let last = self.curriculum.lessons.len() - 1;
+        match event {
+            Event::Key(KeyPress::Esc) => self.done = true,
+            Event::Key(KeyPress::Up) => self.selected = self.selected.saturating_sub(1),
+            Event::Key(KeyPress::Down) => self.selected = (self.selected + 1).min(last),
+            Event::Key(KeyPress::Enter) => self.start(),
+            Event::Key(KeyPress::Char(c)) => {
+                if let Some(i) = c.to_digit(10).and_then(|d| (d as usize).checked_sub(1)) {
+                    self.selected = i.min(last);
+                }
+            }
+            _ => {}
+        }
+        Box::new(self.take())
+    }
+    fn tick(&mut self) -> Box<dyn State<B>> {
+        if let Some(session) = &mut self.session {
+            self.session = Some(session.tick());
+            self.check_session();
+        }
+        Box::new(self.take())
+    }
+    fn tick_rate(&self) -> Duration {
+        match &self.session {
+            Some(session) => session.tick_rate(),
+            None => Duration::from_millis(250),
+        }
+    }
+    fn terminate(&self) -> bool {
+        self.done
+    }
+    fn records(&self) -> Vec<(Record, Option<&KeystrokeLog>)> {
+        self.finished
+            .iter()
+            .map(|(record, log)| (record.clone(), log.as_ref()))
+            .collect()
+    }
+    fn min_size(&self) -> (u16, u16) {
+        match &self.session {
+            Some(session) => session.min_size(),
+            None => (MIN_WIDTH, self.curriculum.lessons.len() as u16 + 4),
+        }
+    }
+    fn ui(&self, frame: &mut tui::Frame<B>) {
+        if let Some(session) = &self.session {
+            return session.ui(frame);
+        }
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(3),
+                Constraint::Length(1),
+                Constraint::Length(1),
+            ])
+            .split(frame.size());
+        frame.render_widget(
+            Paragraph::new(self.lines()).block(
+                Block::default()
+                    .title(" Lessons ")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded),
+            ),
+            chunks[0],
+        );
+        frame.render_widget(Paragraph::new(self.message.as_str()), chunks[1]);
+        frame.render_widget(
+            Paragraph::new("up/down: choose  enter: start  esc: quit"),
+            chunks[2],
+        );
+    }
+}
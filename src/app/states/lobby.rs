@@ -0,0 +1,110 @@
+use super::{Countdown, State, Typing};
+use crate::app::{Event, KeyPress};
+use crate::widgets::Popup;
+use std::{boxed::Box, net::SocketAddr, time::Duration};
+
+/// Waits for racers to join a networked race until the host starts it.
+#[derive(Default)]
+pub struct Lobby {
+    typing: Typing,
+    name: String,
+    countdown: u64,
+    address: Option<SocketAddr>,
+    done: bool,
+}
+
+impl Lobby {
+    /// `typing` must already be linked to the race. The host counts down
+    /// from `countdown` seconds once everyone is in.
+    pub fn new(typing: Typing, name: &str, countdown: u64) -> Lobby {
+        Lobby {
+            typing,
+            name: name.to_string(),
+            countdown,
+            address: None,
+            done: false,
+        }
+    }
+    /// Shows where others can join the race from.
+    pub fn with_address(mut self, address: SocketAddr) -> Lobby {
+        self.address = Some(address);
+        self
+    }
+    fn take(&mut self) -> Lobby {
+        std::mem::take(self)
+    }
+    fn is_host(&self) -> bool {
+        self.typing.link().is_some_and(|link| link.is_host())
+    }
+    fn countdown(&mut self, seconds: u64) -> Countdown {
+        Countdown::new(std::mem::take(&mut self.typing), seconds)
+    }
+    fn message(&self) -> String {
+        if self.typing.link().is_some_and(|link| link.disconnected()) {
+            return "Host disconnected\nesc: quit".to_string();
+        }
+        let mut racers = vec![self.name.clone()];
+        if let Some(link) = self.typing.link() {
+            racers.extend(link.others().into_iter().map(|lane| lane.name));
+        }
+        let listening = self.address.map_or(String::new(), |address| {
+            format!("Listening on port {}\n", address.port())
+        });
+        format!(
+            "{}Racers: {}\n{}",
+            listening,
+            racers.join(", "),
+            if self.is_host() {
+                "enter: start  esc: quit"
+            } else {
+                "Waiting for the host to start  esc: quit"
+            }
+        )
+    }
+}
+
+impl<B> State<B> for Lobby
+where
+    B: tui::backend::Backend,
+{
+    fn handle_event(&mut self, event: Event) -> Box<dyn State<B>> {
+        match event {
+            Event::Key(KeyPress::Esc) => self.done = true,
+            Event::Key(KeyPress::Enter) if self.is_host() => {
+                let countdown = self.countdown;
+                if let Some(link) = self.typing.link_mut() {
+                    link.start(countdown);
+                }
+                return Box::new(self.countdown(countdown));
+            }
+            _ => {}
+        }
+        Box::new(self.take())
+    }
+    fn tick(&mut self) -> Box<dyn State<B>> {
+        if let Some(link) = self.typing.link_mut() {
+            if link.disconnected() {
+                return Box::new(self.take());
+            }
+            if let Some(countdown) = link.started() {
+                return Box::new(self.countdown(countdown));
+            }
+            // lets the host relay who has joined so far
+            link.update(Default::default());
+        }
+        Box::new(self.take())
+    }
+    fn tick_rate(&self) -> Duration {
+        Duration::from_millis(100)
+    }
+    fn terminate(&self) -> bool {
+        self.done
+    }
+    fn min_size(&self) -> (u16, u16) {
+        State::<B>::min_size(&self.typing)
+    }
+    fn ui(&self, frame: &mut tui::Frame<B>) {
+        State::<B>::ui(&self.typing, frame);
+        frame.render_widget(Popup::new(self.message()), frame.size());
+    }
+}
@@ -1,7 +1,17 @@
+mod countdown;
 mod finished;
+mod lesson_map;
+mod lobby;
+mod replay;
 mod state;
+mod tutorial;
 mod typing;
 
+pub use countdown::Countdown;
 pub use finished::Finished;
-pub use state::State;
+pub use lesson_map::LessonMap;
+pub use lobby::Lobby;
+pub use replay::Replay;
+pub use state::{State, MIN_WIDTH};
+pub use tutorial::Tutorial;
 pub use typing::Typing;
@@ -0,0 +1,126 @@
+use super::{State, MIN_WIDTH};
+use crate::app::{Event, KeyPress};
+use crate::session::KeystrokeLog;
+use crate::text_model::TextModel;
+use crate::widgets::TextArea;
+use std::{
+    boxed::Box,
+    error::Error,
+    time::{Duration, Instant},
+};
+use tui::{
+    layout::{Constraint, Direction, Layout},
+    widgets::Paragraph,
+};
+
+const MIN_SPEED: f64 = 0.125;
+const MAX_SPEED: f64 = 16.0;
+
+/// Plays back a keystroke log through the text area.
+#[derive(Default)]
+pub struct Replay {
+    text_model: Option<TextModel>,
+    log: KeystrokeLog,
+    next: usize,
+    position: Duration,
+    speed: f64,
+    playing: bool,
+    last_tick: Option<Instant>,
+    done: bool,
+}
+
+impl Replay {
+    pub fn new(log: KeystrokeLog, speed: f64) -> Result<Replay, Box<dyn Error>> {
+        Ok(Replay {
+            text_model: Some(log.text_model()?),
+            log,
+            speed: speed.clamp(MIN_SPEED, MAX_SPEED),
+            playing: true,
+            last_tick: Some(Instant::now()),
+            ..Replay::default()
+        })
+    }
+    fn take(&mut self) -> Replay {
+        std::mem::take(self)
+    }
+    fn is_over(&self) -> bool {
+        self.next >= self.log.keystrokes.len()
+    }
+    /// Moves the replay on to `now`, applying any keystrokes that are due.
+    fn advance(&mut self, now: Instant) {
+        if let (true, Some(last_tick)) = (self.playing, self.last_tick) {
+            self.position += now.saturating_duration_since(last_tick).mul_f64(self.speed);
+        }
+        self.last_tick = Some(now);
+        let text_model = self.text_model.as_mut().unwrap();
+        while let Some(keystroke) = self.log.keystrokes.get(self.next) {
+            if Duration::from_millis(keystroke.at_ms) > self.position {
+                break;
+            }
+            keystroke.key.apply(text_model);
+            self.next += 1;
+        }
+        if self.is_over() {
+            self.playing = false;
+        }
+    }
+    fn status(&self) -> String {
+        let progress = format!(
+            "{:.1}s / {:.1}s  {}x",
+            self.position.min(self.log.duration()).as_secs_f64(),
+            self.log.duration().as_secs_f64(),
+            self.speed,
+        );
+        if self.is_over() {
+            format!("Replay finished  {}  esc: quit", progress)
+        } else {
+            format!(
+                "Replay  {}  space: {}  +/-: speed  esc: quit",
+                progress,
+                if self.playing { "pause" } else { "play" },
+            )
+        }
+    }
+}
+
+impl<B> State<B> for Replay
+where
+    B: tui::backend::Backend,
+{
+    fn handle_event(&mut self, event: Event) -> Box<dyn State<B>> {
+        self.advance(Instant::now());
+        if let Event::Key(key) = event {
+            match key {
+                KeyPress::Esc => self.done = true,
+                KeyPress::Char(' ') if !self.is_over() => self.playing = !self.playing,
+                KeyPress::Char('+') | KeyPress::Char('=') => {
+                    self.speed = (self.speed * 2.0).min(MAX_SPEED)
+                }
+                KeyPress::Char('-') => self.speed = (self.speed / 2.0).max(MIN_SPEED),
+                _ => {}
+            }
+        }
+        Box::new(self.take())
+    }
+    fn tick(&mut self) -> Box<dyn State<B>> {
+        self.advance(Instant::now());
+        Box::new(self.take())
+    }
+    fn tick_rate(&self) -> Duration {
+        Duration::from_millis(20)
+    }
+    fn terminate(&self) -> bool {
+        self.done
+    }
+    fn min_size(&self) -> (u16, u16) {
+        (MIN_WIDTH, 3 + 1)
+    }
+    fn ui(&self, frame: &mut tui::Frame<B>) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(1)])
+            .split(frame.size());
+        frame.render_widget(TextArea::new(self.text_model.as_ref().unwrap()), chunks[0]);
+        frame.render_widget(Paragraph::new(self.status()), chunks[1]);
+    }
+}
@@ -1,10 +1,45 @@
+use crate::app::Event;
+use crate::history::Record;
+use crate::session::KeystrokeLog;
+use std::time::Duration;
+
+/// The narrowest terminal any state is drawn in.
+pub const MIN_WIDTH: u16 = 20;
+
 pub trait State<B>
 where
     B: tui::backend::Backend,
 {
-    fn handle_event(&mut self, event: crossterm::event::Event) -> std::boxed::Box<dyn State<B>>;
+    fn handle_event(&mut self, event: Event) -> std::boxed::Box<dyn State<B>>;
+    /// Called periodically when no event has arrived.
+    fn tick(&mut self) -> std::boxed::Box<dyn State<B>>;
+    /// How long to wait for an event before calling `tick`.
+    fn tick_rate(&self) -> Duration {
+        Duration::from_millis(250)
+    }
     fn terminate(&self) -> bool {
         false
     }
     fn ui(&self, _: &mut tui::Frame<B>) {}
+    /// The smallest terminal, in columns and rows, that `ui` can draw in.
+    fn min_size(&self) -> (u16, u16) {
+        (MIN_WIDTH, 3)
+    }
+    /// The result to store in the history once the app exits in this state.
+    fn record(&self) -> Option<Record> {
+        None
+    }
+    /// The keys pressed in the session, once it is over.
+    fn keystroke_log(&self) -> Option<&KeystrokeLog> {
+        None
+    }
+    /// Every result to store in the history once the app exits in this
+    /// state, with the keys pressed for it. States that run many sessions
+    /// return them all.
+    fn records(&self) -> Vec<(Record, Option<&KeystrokeLog>)> {
+        self.record()
+            .map(|record| (record, self.keystroke_log()))
+            .into_iter()
+            .collect()
+    }
 }
@@ -0,0 +1,284 @@
+use super::{State, Typing, MIN_WIDTH};
+use crate::app::{Event, KeyPress};
+use crate::history::Record;
+use crate::session::{KeystrokeLog, Rules, Settings};
+use crate::text_model::{Correction, Data};
+use crate::typ::{DrillKind, Exercise, Interpreter, Step, EXIT_LABEL};
+use std::{boxed::Box, error::Error, time::Duration};
+use tui::{
+    layout::{Constraint, Direction, Layout},
+    widgets::{Block, BorderType, Borders, Paragraph},
+};
+
+/// Works through a GNU Typist lesson script, typing each of its exercises
+/// in a session of its own.
+pub struct Tutorial<B> {
+    interpreter: Interpreter,
+    settings: Settings,
+    step: Step,
+    /// The session for the current exercise, until its results are
+    /// dismissed.
+    session: Option<Box<dyn State<B>>>,
+    /// Why the last exercise failed, until the typist has read it.
+    failed: Option<String>,
+    /// The results of every exercise typed, for the history.
+    finished: Vec<(Record, Option<KeystrokeLog>)>,
+}
+
+impl<B> Default for Tutorial<B> {
+    fn default() -> Tutorial<B> {
+        Tutorial {
+            interpreter: Interpreter::default(),
+            settings: Settings::default(),
+            step: Step::Exit,
+            session: None,
+            failed: None,
+            finished: Vec::new(),
+        }
+    }
+}
+
+impl<B> Tutorial<B>
+where
+    B: tui::backend::Backend + 'static,
+{
+    /// Runs `interpreter` from where it is. Exercises are typed with
+    /// `settings`, except that drills may not be corrected.
+    pub fn new(interpreter: Interpreter, settings: Settings) -> Tutorial<B> {
+        let mut tutorial = Tutorial {
+            interpreter,
+            settings,
+            ..Tutorial::default()
+        };
+        tutorial.advance();
+        tutorial
+    }
+    fn take(&mut self) -> Tutorial<B> {
+        std::mem::take(self)
+    }
+    /// Moves on to whatever the script asks for next.
+    fn advance(&mut self) {
+        self.step = self.interpreter.step();
+        if let Step::Exercise(exercise) = &self.step {
+            match self.session_for(exercise) {
+                Ok(session) => self.session = Some(Box::new(session)),
+                // the settings are at fault, so no exercise can be typed
+                Err(e) => {
+                    self.failed = Some(e.to_string());
+                    self.step = Step::Exit;
+                }
+            }
+        }
+    }
+    fn session_for(&self, exercise: &Exercise) -> Result<Typing, Box<dyn Error>> {
+        let settings = Settings {
+            correction: match exercise.drill.kind {
+                DrillKind::Drill => Correction::Disabled,
+                DrillKind::SpeedTest => self.settings.correction,
+            },
+            // exercises are judged once they are over
+            rules: Rules::default(),
+            ..self.settings.clone()
+        };
+        let data = Data {
+            text: exercise.drill.text.clone(),
+            ..Data::default()
+        };
+        // the parser has checked the text can be typed
+        let mut typing = Typing::new(&data.to_xml(), settings)?;
+        let title = match exercise.instruction.trim() {
+            "" => exercise.banner.clone(),
+            instruction => instruction
+                .lines()
+                .map(str::trim)
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+        if !title.is_empty() {
+            typing.set_title(&title);
+        }
+        Ok(typing)
+    }
+    /// Keeps the result of a finished session for the history.
+    fn keep(&mut self, session: &dyn State<B>) -> Option<Record> {
+        let record = session.record()?;
+        let log = session.keystroke_log().cloned();
+        self.finished.push((record.clone(), log));
+        Some(record)
+    }
+    /// Judges the exercise once its session is over.
+    fn check_session(&mut self) {
+        if !self
+            .session
+            .as_ref()
+            .is_some_and(|session| session.terminate())
+        {
+            return;
+        }
+        let record = self
+            .session
+            .take()
+            .and_then(|session| self.keep(session.as_ref()));
+        let exercise = match &self.step {
+            Step::Exercise(exercise) => exercise,
+            _ => return,
+        };
+        match record {
+            Some(record) if record.completed => {
+                let passed = exercise.passes(record.accuracy);
+                self.interpreter.finished(passed);
+                if passed {
+                    self.advance();
+                } else {
+                    self.failed = Some(format!(
+                        "Too many errors: {:.1}%, where at most {}% are allowed",
+                        (1.0 - record.accuracy) * 100.0,
+                        exercise.error_max.unwrap_or_default()
+                    ));
+                }
+            }
+            // left without finishing
+            _ => self.step = Step::Exit,
+        }
+    }
+    /// Answers a menu or question with `key`, or moves on from a page.
+    fn respond(&mut self, key: KeyPress) {
+        if self.failed.take().is_some() {
+            if key != KeyPress::Esc && self.step != Step::Exit {
+                self.advance();
+            } else {
+                self.step = Step::Exit;
+            }
+            return;
+        }
+        match (&self.step, key) {
+            (Step::Menu { menu, .. }, KeyPress::Esc) => {
+                let up = menu.up.clone().unwrap_or_else(|| EXIT_LABEL.to_string());
+                self.interpreter.jump(&up);
+                self.advance();
+            }
+            (_, KeyPress::Esc) => self.step = Step::Exit,
+            (Step::Menu { menu, .. }, KeyPress::Char(c)) => {
+                if let Some((label, _)) = menu_index(c).and_then(|i| menu.items.get(i)) {
+                    let label = label.clone();
+                    self.interpreter.jump(&label);
+                    self.advance();
+                }
+            }
+            (Step::Query { .. }, KeyPress::Char(c @ ('y' | 'Y' | 'n' | 'N'))) => {
+                self.interpreter.answer(c.eq_ignore_ascii_case(&'y'));
+                self.advance();
+            }
+            (Step::Page { .. }, _) => self.advance(),
+            _ => {}
+        }
+    }
+    /// The title, body and key help of a page that is not an exercise.
+    fn page(&self) -> (String, String, &'static str) {
+        if let Some(failed) = &self.failed {
+            let help = match self.step {
+                Step::Exit => "any key: quit",
+                _ => "any key: try again  esc: quit",
+            };
+            return (String::new(), failed.clone(), help);
+        }
+        match &self.step {
+            Step::Page { banner, text } => {
+                (banner.clone(), text.clone(), "any key: continue  esc: quit")
+            }
+            Step::Menu { banner, menu } => {
+                let items = menu
+                    .items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (_, description))| format!("{}. {}", menu_key(i), description));
+                let body = std::iter::once(menu.title.clone())
+                    .chain(std::iter::once(String::new()))
+                    .chain(items)
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let help = match menu.up {
+                    Some(_) => "choose by number  esc: back",
+                    None => "choose by number  esc: quit",
+                };
+                (banner.clone(), body, help)
+            }
+            Step::Query { banner, question } => {
+                (banner.clone(), question.clone(), "y: yes  n: no  esc: quit")
+            }
+            _ => (String::new(), String::new(), ""),
+        }
+    }
+}
+
+/// The key that chooses the `i`th item of a menu: the digits, then letters.
+fn menu_key(i: usize) -> char {
+    std::char::from_digit(i as u32 + 1, 36).unwrap_or('?')
+}
+
+fn menu_index(key: char) -> Option<usize> {
+    (key.to_digit(36)? as usize).checked_sub(1)
+}
+
+impl<B> State<B> for Tutorial<B>
+where
+    B: tui::backend::Backend + 'static,
+{
+    fn handle_event(&mut self, event: Event) -> Box<dyn State<B>> {
+        match (&mut self.session, event) {
+            (Some(session), event) => {
+                self.session = Some(session.handle_event(event));
+                self.check_session();
+            }
+            (None, Event::Key(key)) => self.respond(key),
+            _ => {}
+        }
+        Box::new(self.take())
+    }
+    fn tick(&mut self) -> Box<dyn State<B>> {
+        if let Some(session) = &mut self.session {
+            self.session = Some(session.tick());
+            self.check_session();
+        }
+        Box::new(self.take())
+    }
+    fn tick_rate(&self) -> Duration {
+        match &self.session {
+            Some(session) => session.tick_rate(),
+            None => Duration::from_millis(250),
+        }
+    }
+    fn terminate(&self) -> bool {
+        self.session.is_none() && self.failed.is_none() && self.step == Step::Exit
+    }
+    fn records(&self) -> Vec<(Record, Option<&KeystrokeLog>)> {
+        self.finished
+            .iter()
+            .map(|(record, log)| (record.clone(), log.as_ref()))
+            .collect()
+    }
+    fn min_size(&self) -> (u16, u16) {
+        match &self.session {
+            Some(session) => session.min_size(),
+            None => (MIN_WIDTH, 4),
+        }
+    }
+    fn ui(&self, frame: &mut tui::Frame<B>) {
+        if let Some(session) = &self.session {
+            return session.ui(frame);
+        }
+        let (title, body, help) = self.page();
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(1)])
+            .split(frame.size());
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded);
+        if !title.is_empty() {
+            block = block.title(format!(" {} ", title));
+        }
+        frame.render_widget(Paragraph::new(body).block(block), chunks[0]);
+        frame.render_widget(Paragraph::new(help), chunks[1]);
+    }
+}
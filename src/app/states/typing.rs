@@ -1,26 +1,329 @@
-use super::{Finished, State};
-use crate::text_model::TextModel;
-use crate::widgets::TextArea;
-use crossterm::event::{Event, KeyCode};
+use super::{Finished, State, MIN_WIDTH};
+use crate::app::{Event, KeyPress};
+use crate::difficulty::{Difficulty, Index};
+use crate::generator::Generator;
+use crate::race::{Bot, LaneInfo, Link, Standing};
+use crate::session::{Analysis, Failure, Ghost, Key, KeystrokeLog, Settings, Stats, Stopwatch};
+use crate::text_model::{Data, TextModel};
+use crate::widgets::{Keyboard, Lane, Lanes, Popup, Presentation, TextArea};
 use std::{
     boxed::Box,
     error::Error,
+    time::Instant,
+};
+use tui::{
+    layout::{Alignment, Constraint, Direction, Layout},
+    widgets::Paragraph,
 };
 
 #[derive(Default)]
 pub struct Typing {
     text_model: Option<TextModel>,
+    settings: Settings,
+    stopwatch: Stopwatch,
+    last_input: Option<Instant>,
+    log: KeystrokeLog,
+    ghost: Option<Ghost>,
+    bots: Vec<Bot>,
+    link: Option<Box<dyn Link>>,
+    title: Option<String>,
+    generator: Option<Generator>,
 }
 
+/// How much untyped text to keep ahead of the cursor when the text is
+/// generated as it is typed.
+const GENERATED_AHEAD: usize = 200;
+
 impl Typing {
-    pub fn new(text_file_content: &str) -> Result<Typing, Box<dyn Error>> {
+    pub fn new(text_file_content: &str, settings: Settings) -> Result<Typing, Box<dyn Error>> {
+        let mut text_model = TextModel::from_string(text_file_content)?;
+        text_model.set_correction(settings.correction);
+        let bots = settings
+            .bots
+            .iter()
+            .enumerate()
+            .map(|(i, profile)| {
+                let seed = settings.seed.wrapping_add(i as u64);
+                Bot::new(profile.clone(), text_file_content, seed)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
         Ok(Typing {
-            text_model: Some(TextModel::from_string(text_file_content)?),
+            text_model: Some(text_model),
+            bots,
+            log: KeystrokeLog::new(text_file_content, settings.correction),
+            settings,
+            ..Typing::default()
         })
     }
+    pub fn text_id(&self) -> String {
+        self.text_model.as_ref().unwrap().id()
+    }
+    /// Shows `title` above the text.
+    pub fn set_title(&mut self, title: &str) {
+        self.title = Some(title.to_string());
+    }
+    /// Keeps adding text from `generator` as the text is typed, so that it
+    /// never ends.
+    pub fn set_generator(&mut self, generator: Generator) {
+        self.generator = Some(generator);
+        self.top_up();
+    }
+    fn top_up(&mut self) {
+        let (generator, text_model) = match (&mut self.generator, &mut self.text_model) {
+            (Some(generator), Some(text_model)) => (generator, text_model),
+            _ => return,
+        };
+        let ahead = text_model.characters().len() - text_model.cursor();
+        if ahead >= GENERATED_AHEAD {
+            return;
+        }
+        text_model.extend(&generator.generate(GENERATED_AHEAD));
+    }
+    pub fn set_ghost(&mut self, ghost: Ghost) {
+        self.ghost = Some(ghost);
+    }
+    /// Races against the other typists on the end of `link`.
+    pub fn set_link(&mut self, link: Box<dyn Link>) {
+        self.link = Some(link);
+    }
+    pub fn link(&self) -> Option<&dyn Link> {
+        self.link.as_deref()
+    }
+    pub fn link_mut(&mut self) -> Option<&mut Box<dyn Link>> {
+        self.link.as_mut()
+    }
     fn take(&mut self) -> Typing {
-        Typing {
-            text_model: self.text_model.take(),
+        std::mem::take(self)
+    }
+    /// Starts the clock without waiting for the first keystroke.
+    pub fn start(&mut self, now: Instant) {
+        self.input(now);
+    }
+    pub fn finish(&mut self, now: Instant) -> Finished {
+        let stats = self.stats(now);
+        self.finished(stats, None)
+    }
+    fn finished(&mut self, stats: Stats, failure: Option<Failure>) -> Finished {
+        let time = Some(stats.elapsed).filter(|_| stats.completed && failure.is_none());
+        let mut standings = Vec::new();
+        if !self.bots.is_empty() || self.link.is_some() {
+            standings.push(Standing {
+                name: "You".to_string(),
+                time,
+            });
+            standings.extend(self.bots.iter().map(|bot| Standing {
+                name: bot.name().to_string(),
+                time: bot.finish_time(),
+            }));
+        }
+        let seed = Some(self.settings.seed).filter(|_| !self.bots.is_empty());
+        let lane = LaneInfo {
+            time_ms: time.map(|t| t.as_millis() as u64),
+            done: true,
+            ..self.lane()
+        };
+        let analysis = self.settings.layout.as_ref().map(|layout| {
+            Analysis::new(self.text_model.as_ref().unwrap(), &self.log, layout)
+        });
+        let text_model = self.text_model.take().unwrap();
+        if self.generator.is_some() {
+            // the log is replayed against all the text generated, which is
+            // only written out once it stops growing
+            let data = Data {
+                text: text_model.text(),
+                ..Data::default()
+            };
+            self.log.source = data.to_xml();
+        }
+        let difficulty = self.difficulty(&text_model.text());
+        let mut finished = Finished::new(
+            text_model,
+            stats,
+            failure,
+            std::mem::take(&mut self.log),
+        )
+        .with_race(Standing::rank(standings), seed)
+        .with_difficulty(difficulty.score);
+        if let Some(analysis) = analysis {
+            finished = finished.with_analysis(analysis);
+        }
+        match self.link.take() {
+            Some(mut link) => {
+                link.update(lane.clone());
+                finished.with_link(link, lane)
+            }
+            None => finished,
+        }
+    }
+    /// How hard `text` is on the layout shown, from the settings' index if
+    /// they have one, or else scored afresh.
+    fn difficulty(&self, text: &str) -> Difficulty {
+        let layout = self.settings.layout.as_ref();
+        let indexed = match (&self.settings.difficulty_index, &self.generator) {
+            // generated text is never typed again, so isn't worth keeping
+            (Some(path), None) => Index::score_in(path, text, layout).ok(),
+            _ => None,
+        };
+        indexed.unwrap_or_else(|| Difficulty::new(text, layout))
+    }
+    fn stats(&self, now: Instant) -> Stats {
+        Stats::new(
+            self.text_model.as_ref().unwrap(),
+            self.stopwatch.elapsed(now),
+            self.stopwatch.idle(now),
+        )
+    }
+    /// Finishes the session if it is complete or has broken the rules.
+    fn check(&mut self, now: Instant) -> Option<Finished> {
+        let stats = self.stats(now);
+        let failure = self.settings.rules.check(&stats);
+        if failure.is_none() && !stats.completed {
+            return None;
+        }
+        Some(self.finished(stats, failure))
+    }
+    fn input(&mut self, now: Instant) {
+        self.last_input = Some(now);
+        self.stopwatch.start(now);
+    }
+    fn press(&mut self, key: Key, now: Instant) {
+        self.input(now);
+        self.log.press(
+            key,
+            self.text_model.as_mut().unwrap(),
+            self.stopwatch.elapsed(now),
+        );
+        self.top_up();
+        self.share();
+    }
+    fn share(&mut self) {
+        let lane = self.lane();
+        if let Some(link) = &mut self.link {
+            link.update(lane);
+        }
+    }
+    /// Who wrote the text, and the keys that control the session.
+    fn header(&self) -> (String, &'static str) {
+        let author = self.text_model.as_ref().unwrap().author();
+        let keys = if self.link.is_some() {
+            "esc: finish"
+        } else {
+            "esc: finish  ctrl+p: pause"
+        };
+        (author.map_or(String::new(), |a| format!("by {}", a)), keys)
+    }
+    fn status(&self, now: Instant) -> String {
+        let elapsed = self.stopwatch.elapsed(now);
+        let mut status = format!("{:.1}s", elapsed.as_secs_f64());
+        let cursor = self.text_model.as_ref().unwrap().cursor();
+        if let Some(ghost) = &self.ghost {
+            status += &versus("ghost", cursor, ghost.position(elapsed));
+        }
+        if let Some(pacer) = &self.settings.pacer {
+            status += &versus("pacer", cursor, pacer.position(elapsed));
+        }
+        status
+    }
+    /// Our own progress, to share with a networked race.
+    fn lane(&self) -> LaneInfo {
+        LaneInfo {
+            position: self.text_model.as_ref().unwrap().cursor(),
+            ..LaneInfo::default()
+        }
+    }
+    /// A lane per racer, if there is anyone to race against.
+    fn lanes(&self, now: Instant) -> Option<Lanes> {
+        if self.bots.is_empty() && self.link.is_none() {
+            return None;
+        }
+        let text_model = self.text_model.as_ref().unwrap();
+        let length = text_model.characters().len().max(1) as f64;
+        let elapsed = self.stopwatch.elapsed(now);
+        let mut lanes = vec![Lane {
+            name: "You".to_string(),
+            progress: text_model.cursor() as f64 / length,
+            highlight: true,
+        }];
+        lanes.extend(self.bots.iter().map(|bot| Lane {
+            name: bot.name().to_string(),
+            progress: bot.position(elapsed) as f64 / length,
+            highlight: false,
+        }));
+        if let Some(link) = &self.link {
+            lanes.extend(link.others().into_iter().map(|lane| Lane {
+                progress: lane.position as f64 / length,
+                name: lane.name,
+                highlight: false,
+            }));
+        }
+        Some(Lanes::new(lanes))
+    }
+    /// The character `c` types in the layout being practised, if the
+    /// system is set to another.
+    fn translate(&self, c: char) -> char {
+        match (&self.settings.os_layout, &self.settings.layout) {
+            (Some(os_layout), Some(layout)) => os_layout.translate(c, layout),
+            _ => c,
+        }
+    }
+    /// The keyboard, highlighting the next character to type, if the
+    /// settings have a layout.
+    fn keyboard(&self) -> Option<Keyboard<'_>> {
+        let text_model = self.text_model.as_ref().unwrap();
+        let next = text_model.characters().nth(text_model.cursor());
+        let keyboard = Keyboard::new(self.settings.layout.as_ref()?);
+        Some(keyboard.highlight(next.map(|c| c.value())))
+    }
+    /// Handles a key pressed at `now`, returning the results if it ended
+    /// the session.
+    pub fn key(&mut self, key: KeyPress, now: Instant) -> Option<Finished> {
+        match key {
+            KeyPress::Esc => Some(self.finish(now)),
+            _ if self.stopwatch.is_paused() => {
+                self.stopwatch.resume(now);
+                self.input(now);
+                None
+            }
+            // racers over a link can't stop the clock to read ahead
+            KeyPress::Ctrl('p') if self.link.is_none() => {
+                self.stopwatch.pause(now);
+                None
+            }
+            KeyPress::Char(c) => {
+                self.press(Key::Char(self.translate(c)), now);
+                self.check(now)
+            }
+            KeyPress::Enter => {
+                self.press(Key::Char('\n'), now);
+                self.check(now)
+            }
+            KeyPress::Backspace => {
+                self.press(Key::Backspace, now);
+                self.check(now)
+            }
+            _ => None,
+        }
+    }
+    /// Moves the session on to `now` without input, pausing it if it has
+    /// been idle too long. Sessions linked to a race never pause.
+    pub fn advance(&mut self, now: Instant) -> Option<Finished> {
+        self.share();
+        let idle_timeout = self.settings.idle_timeout.filter(|_| self.link.is_none());
+        if let (Some(timeout), Some(last_input)) = (idle_timeout, self.last_input) {
+            if self.stopwatch.is_running() && now.saturating_duration_since(last_input) >= timeout {
+                self.stopwatch.pause(last_input);
+            }
+        }
+        if self.stopwatch.is_running() {
+            return self.check(now);
+        }
+        None
+    }
+    /// The state after this one, given the results if the session is over.
+    fn next<B: tui::backend::Backend>(&mut self, finished: Option<Finished>) -> Box<dyn State<B>> {
+        match finished {
+            Some(finished) => Box::new(finished),
+            None => Box::new(self.take()),
         }
     }
 }
@@ -29,27 +332,79 @@ impl<B> State<B> for Typing
 where
     B: tui::backend::Backend,
 {
-    fn handle_event(&mut self, event: crossterm::event::Event) -> Box<dyn State<B>> {
-        match event {
-            Event::Key(key) => match key.code {
-                KeyCode::Esc => Box::new(Finished {}),
-                KeyCode::Char(c) => {
-                    self.text_model.as_mut().unwrap().type_character(c);
-                    Box::new(self.take())
-                }
-                KeyCode::Backspace => {
-                    self.text_model.as_mut().unwrap().backspace();
-                    Box::new(self.take())
-                }
-                _ => Box::new(self.take()),
-            },
-            _ => Box::new(self.take()),
-        }
+    fn handle_event(&mut self, event: Event) -> Box<dyn State<B>> {
+        let finished = match event {
+            Event::Key(key) => self.key(key, Instant::now()),
+            _ => None,
+        };
+        self.next(finished)
+    }
+    fn tick(&mut self) -> Box<dyn State<B>> {
+        let finished = self.advance(Instant::now());
+        self.next(finished)
+    }
+    fn min_size(&self) -> (u16, u16) {
+        let lanes = self.lanes(Instant::now()).map_or(0, |lanes| lanes.height());
+        let keyboard = self.keyboard().map_or(0, |_| Keyboard::height());
+        // the text area needs a line inside its borders, plus the header
+        // and status lines
+        (MIN_WIDTH, 1 + lanes + 3 + keyboard + 1)
     }
     fn ui(&self, frame: &mut tui::Frame<B>) {
-        frame.render_widget(
-            TextArea::new(self.text_model.as_ref().unwrap()),
-            frame.size(),
-        );
+        let presentation = if self.settings.blind {
+            Presentation::Blind
+        } else {
+            Presentation::Feedback
+        };
+        let now = Instant::now();
+        let lanes = self.lanes(now);
+        let keyboard = self.keyboard();
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(lanes.as_ref().map_or(0, Lanes::height)),
+                Constraint::Min(3),
+                Constraint::Length(keyboard.as_ref().map_or(0, |_| Keyboard::height())),
+                Constraint::Length(1),
+            ])
+            .split(frame.size());
+        let (author, keys) = self.header();
+        frame.render_widget(Paragraph::new(author), chunks[0]);
+        frame.render_widget(Paragraph::new(keys).alignment(Alignment::Right), chunks[0]);
+        if let Some(lanes) = lanes {
+            frame.render_widget(lanes, chunks[1]);
+        }
+        let mut text_area =
+            TextArea::with_presentation(self.text_model.as_ref().unwrap(), presentation);
+        if let Some(ghost) = &self.ghost {
+            text_area = text_area.ghost(ghost.position(self.stopwatch.elapsed(now)));
+        }
+        if let Some(pacer) = &self.settings.pacer {
+            text_area = text_area.pacer(pacer.position(self.stopwatch.elapsed(now)));
+        }
+        if let Some(title) = &self.title {
+            text_area = text_area.title(title);
+        }
+        frame.render_widget(text_area, chunks[2]);
+        if let Some(keyboard) = keyboard {
+            frame.render_widget(keyboard, chunks[3]);
+        }
+        frame.render_widget(Paragraph::new(self.status(now)), chunks[4]);
+        if self.stopwatch.is_paused() {
+            frame.render_widget(
+                Popup::new("Paused\n\nPress any key to resume"),
+                frame.size(),
+            );
+        }
+    }
+}
+
+/// Describes how far the typist's cursor is from an opponent's.
+fn versus(opponent: &str, cursor: usize, position: usize) -> String {
+    match cursor.cmp(&position) {
+        std::cmp::Ordering::Greater => format!("  {} ahead of {}", cursor - position, opponent),
+        std::cmp::Ordering::Less => format!("  {} behind {}", position - cursor, opponent),
+        std::cmp::Ordering::Equal => format!("  level with {}", opponent),
     }
 }
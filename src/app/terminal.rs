@@ -0,0 +1,146 @@
+use crossterm::{
+    event::DisableMouseCapture,
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use std::{
+    io,
+    ops::{Deref, DerefMut},
+    panic,
+    sync::{Arc, Mutex, TryLockError},
+    thread,
+};
+use tui::{backend::CrosstermBackend, Terminal};
+
+type PanicHook = Box<dyn Fn(&panic::PanicHookInfo<'_>) + Sync + Send + 'static>;
+
+/// A writer shared between the terminal and the panic hook, so that the
+/// hook restores the terminal the app was drawing on.
+pub struct SharedWriter<W>(Arc<Mutex<W>>);
+
+impl<W> io::Write for SharedWriter<W>
+where
+    W: io::Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        lock(&self.0)?.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        lock(&self.0)?.flush()
+    }
+}
+
+/// Locks `writer`, even if a panic poisoned it, unless this thread already
+/// holds it.
+fn lock<W>(writer: &Mutex<W>) -> io::Result<std::sync::MutexGuard<'_, W>> {
+    match writer.try_lock() {
+        Ok(writer) => Ok(writer),
+        Err(TryLockError::Poisoned(poisoned)) => Ok(poisoned.into_inner()),
+        Err(TryLockError::WouldBlock) => Err(io::ErrorKind::WouldBlock.into()),
+    }
+}
+
+/// A terminal in raw mode on the alternate screen, which is put back as it
+/// was when the guard is dropped, or if the app panics.
+pub struct TerminalGuard<W>
+where
+    W: io::Write + Send + 'static,
+{
+    terminal: Terminal<CrosstermBackend<SharedWriter<W>>>,
+    previous_hook: Option<Arc<PanicHook>>,
+    restored: bool,
+}
+
+impl<W> TerminalGuard<W>
+where
+    W: io::Write + Send + 'static,
+{
+    pub fn new(buffer: W) -> io::Result<TerminalGuard<W>> {
+        let writer = Arc::new(Mutex::new(buffer));
+        let backend = CrosstermBackend::new(SharedWriter(writer.clone()));
+        let terminal = Terminal::new(backend)?;
+        enable_raw_mode()?;
+        // restore before the panic message is printed, so it is not lost
+        // on the alternate screen. Panics in other threads, such as a race's
+        // network threads, leave the app drawing and the terminal as it is.
+        let previous_hook = Arc::new(panic::take_hook());
+        let hook = previous_hook.clone();
+        let app_thread = thread::current().id();
+        panic::set_hook(Box::new(move |info| {
+            if thread::current().id() == app_thread {
+                let _ = restore(&mut SharedWriter(writer.clone()));
+            }
+            hook(info);
+        }));
+        let mut guard = TerminalGuard {
+            terminal,
+            previous_hook: Some(previous_hook),
+            restored: false,
+        };
+        execute!(guard.terminal.backend_mut(), EnterAlternateScreen)?;
+        Ok(guard)
+    }
+
+    /// Restores the terminal, reporting anything that goes wrong.
+    pub fn restore(mut self) -> io::Result<()> {
+        self.restored = true;
+        self.restore_panic_hook();
+        restore(self.terminal.backend_mut())?;
+        self.terminal.show_cursor()
+    }
+
+    /// Puts back the panic hook there was before this guard's.
+    fn restore_panic_hook(&mut self) {
+        // hooks can't be changed while panicking
+        if thread::panicking() {
+            return;
+        }
+        if let Some(previous_hook) = self.previous_hook.take() {
+            drop(panic::take_hook());
+            match Arc::try_unwrap(previous_hook) {
+                Ok(hook) => panic::set_hook(hook),
+                Err(hook) => panic::set_hook(Box::new(move |info| hook(info))),
+            }
+        }
+    }
+}
+
+fn restore<W: io::Write>(buffer: &mut W) -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(buffer, LeaveAlternateScreen, DisableMouseCapture)
+}
+
+impl<W> Drop for TerminalGuard<W>
+where
+    W: io::Write + Send + 'static,
+{
+    fn drop(&mut self) {
+        if self.restored {
+            return;
+        }
+        self.restore_panic_hook();
+        let _ = restore(self.terminal.backend_mut());
+        let _ = self.terminal.show_cursor();
+    }
+}
+
+impl<W> Deref for TerminalGuard<W>
+where
+    W: io::Write + Send + 'static,
+{
+    type Target = Terminal<CrosstermBackend<SharedWriter<W>>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.terminal
+    }
+}
+
+impl<W> DerefMut for TerminalGuard<W>
+where
+    W: io::Write + Send + 'static,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.terminal
+    }
+}
@@ -0,0 +1,526 @@
+use super::script::DEFAULT_STEP;
+use super::*;
+use crate::lessons::{Curriculum, Progress};
+use crate::session::{Rules, Settings};
+use crate::text_model::{Format, ImportOptions};
+use std::time::Duration;
+use tui::{backend::TestBackend, Terminal};
+
+const SOURCE: &str = "<klata_text><text>I am</text></klata_text>";
+
+fn run(script: &str, settings: Settings) -> crate::history::Record {
+    run_script(SOURCE, settings, &Script::parse(script).unwrap()).unwrap()
+}
+
+#[test]
+fn script_parses_characters_and_special_keys() {
+    let script = Script::parse("a\n<bs><lt><ctrl-p>").unwrap();
+    let keys = script.steps.iter().map(|s| s.key).collect::<Vec<_>>();
+    assert_eq!(
+        keys,
+        [
+            KeyPress::Char('a'),
+            KeyPress::Backspace,
+            KeyPress::Char('<'),
+            KeyPress::Ctrl('p')
+        ]
+    );
+}
+
+#[test]
+fn script_timestamps_set_the_time_of_the_next_key() {
+    let script = Script::parse("a<@1000>bc").unwrap();
+    let times = script.steps.iter().map(|s| s.at).collect::<Vec<_>>();
+    assert_eq!(
+        times,
+        [
+            Duration::ZERO,
+            Duration::from_secs(1),
+            Duration::from_secs(1) + DEFAULT_STEP
+        ]
+    );
+}
+
+#[test]
+fn malformed_scripts_are_rejected() {
+    assert!(Script::parse("<nope>").is_err());
+    assert!(Script::parse("<@soon>").is_err());
+}
+
+#[test]
+fn script_completes_the_text() {
+    let record = run("I<@1000>x<bs> am", Settings::default());
+    assert!(record.completed);
+    assert_eq!(record.errors, 1);
+    assert_eq!(record.elapsed_secs, 1.8);
+}
+
+#[test]
+fn enter_types_line_breaks() {
+    let options = ImportOptions {
+        format: Some(Format::Text),
+        ..ImportOptions::default()
+    };
+    let path = std::env::temp_dir().join(format!("klata-enter-{}", std::process::id()));
+    std::fs::write(&path, "One\n\nTwo").unwrap();
+    let source = read_text(path.to_str().unwrap(), &options, 0).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let script = Script::parse("One<enter>Two").unwrap();
+    let record = run_script(&source, Settings::default(), &script).unwrap();
+    assert!(record.completed);
+    assert_eq!(record.errors, 0);
+}
+
+#[test]
+fn seed_chooses_the_text_from_a_library() {
+    let texts = ["one", "two", "three", "four", "five"].map(|text| crate::text_model::Data {
+        text: text.to_string(),
+        ..Default::default()
+    });
+    let path = std::env::temp_dir().join(format!("klata-library-{}.xml", std::process::id()));
+    std::fs::write(&path, crate::text_model::library_to_xml(&texts)).unwrap();
+    let path = path.to_str().unwrap();
+    let options = ImportOptions::default();
+    let chosen = (0..20)
+        .map(|seed| read_text(path, &options, seed).unwrap())
+        .collect::<std::collections::HashSet<_>>();
+    let first = read_text(path, &options, 7).unwrap();
+    let again = read_text(path, &options, 7).unwrap();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(first, again);
+    assert!(chosen.len() > 1);
+}
+
+#[test]
+fn finished_sessions_are_scored_through_the_index() {
+    let path = std::env::temp_dir().join(format!("klata-typed-index-{}.json", std::process::id()));
+    let settings = Settings {
+        difficulty_index: Some(path.clone()),
+        ..Settings::default()
+    };
+    let record = run("I am", settings);
+    let saved = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(saved.contains(&crate::text_model::text_id("I am")));
+    let scored = crate::difficulty::Difficulty::new("I am", None).score;
+    assert!((record.difficulty.unwrap() - scored).abs() < 1e-9);
+}
+
+#[test]
+fn keys_are_translated_to_the_practised_layout() {
+    let settings = Settings {
+        layout: crate::layout::Layout::built_in("colemak"),
+        os_layout: crate::layout::Layout::built_in("qwerty"),
+        ..Settings::default()
+    };
+    // on Colemak, I is where L is on QWERTY
+    let record = run("L am", settings);
+    assert!(record.completed);
+    assert_eq!(record.errors, 0);
+}
+
+#[test]
+fn script_that_runs_out_finishes_the_session() {
+    let record = run("I ", Settings::default());
+    assert!(!record.completed);
+    assert_eq!(record.elapsed_secs, DEFAULT_STEP.as_secs_f64());
+}
+
+#[test]
+fn script_breaks_the_rules() {
+    let settings = Settings {
+        rules: Rules {
+            sudden_death: true,
+            ..Rules::default()
+        },
+        ..Settings::default()
+    };
+    let record = run("Ix am", settings);
+    assert!(record.failure.is_some());
+    assert!(!record.completed);
+}
+
+#[test]
+fn idle_gaps_pause_the_session() {
+    let record = run("I<@30000>  am", Settings::default());
+    assert!(record.completed);
+    // the first key after the gap only resumes the session
+    assert_eq!(record.idle_secs, 30.0);
+    assert_eq!(record.elapsed_secs, 0.6);
+}
+
+/// The far end of a race with nobody else in it.
+struct Alone;
+
+impl crate::race::Link for Alone {
+    fn is_host(&self) -> bool {
+        true
+    }
+    fn start(&mut self, _countdown: u64) {}
+    fn started(&mut self) -> Option<u64> {
+        None
+    }
+    fn update(&mut self, _lane: crate::race::LaneInfo) {}
+    fn others(&self) -> Vec<crate::race::LaneInfo> {
+        Vec::new()
+    }
+    fn disconnected(&self) -> bool {
+        false
+    }
+}
+
+#[test]
+fn linked_races_never_pause() {
+    let mut typing = states::Typing::new(SOURCE, Settings::default()).unwrap();
+    typing.set_link(std::boxed::Box::new(Alone));
+    let start = std::time::Instant::now();
+    typing.key(KeyPress::Char('I'), start);
+    typing.key(KeyPress::Ctrl('p'), start);
+    assert!(typing.advance(start + Duration::from_secs(30)).is_none());
+    let record = typing.finish(start + Duration::from_secs(30)).result();
+    assert_eq!(record.idle_secs, 0.0);
+    assert_eq!(record.elapsed_secs, 30.0);
+}
+
+/// Events from an iterator, delivered as fast as the app takes them.
+struct ScriptedEvents<I>(I);
+
+impl<I> EventSource for ScriptedEvents<I>
+where
+    I: Iterator<Item = Event>,
+{
+    fn next_event(&mut self, _tick_rate: Duration) -> std::io::Result<Option<Event>> {
+        Ok(self.0.next())
+    }
+}
+
+/// Drives an app through `events` on a test terminal, returning what is
+/// on screen at the end along with the app.
+fn drive(events: Vec<Event>) -> (String, App<TestBackend>) {
+    drive_on(TestBackend::new(40, 12), events)
+}
+
+fn drive_on(backend: TestBackend, events: Vec<Event>) -> (String, App<TestBackend>) {
+    drive_app(App::new(SOURCE, Settings::default()).unwrap(), backend, events)
+}
+
+fn drive_app(
+    mut app: App<TestBackend>,
+    backend: TestBackend,
+    events: Vec<Event>,
+) -> (String, App<TestBackend>) {
+    let mut terminal = Terminal::new(backend).unwrap();
+    app.drive(&mut terminal, &mut ScriptedEvents(events.into_iter()))
+        .unwrap();
+    let screen = terminal
+        .backend()
+        .buffer()
+        .content
+        .iter()
+        .map(|cell| cell.symbol.as_str())
+        .collect();
+    (screen, app)
+}
+
+fn keys(s: &str) -> Vec<Event> {
+    s.chars().map(|c| Event::Key(KeyPress::Char(c))).collect()
+}
+
+#[test]
+fn app_shows_the_text_until_it_is_typed() {
+    let (screen, app) = drive(keys("I a"));
+    assert!(screen.contains("I am"));
+    assert!(!screen.contains("Results"));
+    assert!(!app.is_done());
+}
+
+#[test]
+fn app_shows_results_once_the_text_is_typed() {
+    let (screen, app) = drive(keys("I am"));
+    assert!(screen.contains("Results"));
+    assert!(screen.contains("Errors: 0"));
+    assert!(screen.contains("Difficulty: "));
+    assert!(!app.is_done());
+}
+
+#[test]
+fn app_exits_on_a_key_after_the_results() {
+    let mut events = keys("I am");
+    events.push(Event::Key(KeyPress::Enter));
+    events.extend(keys("more keys are never read"));
+    let (_, app) = drive(events);
+    assert!(app.is_done());
+}
+
+#[test]
+fn app_pauses_on_ctrl_p() {
+    let mut events = keys("I");
+    events.push(Event::Key(KeyPress::Ctrl('p')));
+    events.push(Event::Tick);
+    let (screen, _) = drive(events);
+    assert!(screen.contains("Paused"));
+}
+
+#[test]
+fn app_shows_the_author_and_keys_in_a_header() {
+    let source = "<klata_text><text>I am</text><author>Ishmael</author></klata_text>";
+    let app = App::new(source, Settings::default()).unwrap();
+    let (screen, _) = drive_app(app, TestBackend::new(60, 12), keys("I"));
+    let header = screen.chars().take(60).collect::<String>();
+    assert!(header.starts_with("by Ishmael"));
+    assert!(header.ends_with("esc: finish  ctrl+p: pause"));
+}
+
+#[test]
+fn app_says_when_the_terminal_is_too_small() {
+    let (screen, _) = drive_on(TestBackend::new(12, 3), keys("I"));
+    assert!(screen.contains("need 20x5"));
+    assert!(!screen.contains("I am"));
+}
+
+#[test]
+fn app_shows_a_keyboard_in_the_chosen_layout() {
+    let settings = Settings {
+        layout: crate::layout::Layout::built_in("dvorak"),
+        ..Settings::default()
+    };
+    let app = App::new(SOURCE, settings).unwrap();
+    let (screen, _) = drive_app(app, TestBackend::new(60, 12), keys("I"));
+    assert!(screen.contains(" a   o   e   u "));
+    assert!(screen.contains("space"));
+    let (screen, _) = drive(keys("I"));
+    assert!(!screen.contains("space"));
+}
+
+#[test]
+fn results_analyse_fingers_with_a_layout() {
+    let settings = Settings {
+        layout: crate::layout::Layout::built_in("qwerty"),
+        ..Settings::default()
+    };
+    let app = App::new(SOURCE, settings).unwrap();
+    let (screen, _) = drive_app(app, TestBackend::new(60, 20), keys("I am"));
+    assert!(screen.contains("Hands: 33% left, 67% right"));
+    assert!(screen.contains("Slowest finger"));
+    let (screen, _) = drive(keys("I am"));
+    assert!(!screen.contains("Hands"));
+}
+
+#[test]
+fn keys_end_a_countdown_that_is_over() {
+    let settings = Settings {
+        start: crate::session::Start::Countdown(0),
+        ..Settings::default()
+    };
+    let app = App::new(SOURCE, settings).unwrap();
+    // no ticks, as when keys keep coming
+    let (screen, _) = drive_app(app, TestBackend::new(40, 12), keys("I am"));
+    assert!(screen.contains("Results"));
+}
+
+#[test]
+fn resizing_is_not_a_key_press() {
+    let mut events = keys("I am");
+    events.push(Event::Resize(40, 12));
+    let (screen, app) = drive(events);
+    assert!(screen.contains("Results"));
+    assert!(!app.is_done());
+}
+
+fn tutorial(events: Vec<Event>) -> (String, App<TestBackend>) {
+    static RUNS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let run = RUNS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    let name = format!("klata-tutorial-{}-{}.typ", std::process::id(), run);
+    let path = std::env::temp_dir().join(name);
+    std::fs::write(&path, "B:Basics\nT:Welcome\n*:DRILL\nI:Type it\nD:ab\nQ:Again?\nY:DRILL\n")
+        .unwrap();
+    let app = App::tutorial(&path, None, Settings::default());
+    std::fs::remove_file(&path).unwrap();
+    drive_app(app.unwrap(), TestBackend::new(40, 12), events)
+}
+
+#[test]
+fn tutorial_pages_lead_to_drills() {
+    let (screen, _) = tutorial(vec![]);
+    assert!(screen.contains("Basics"));
+    assert!(screen.contains("Welcome"));
+    let (screen, _) = tutorial(keys(" "));
+    assert!(screen.contains("Type it"));
+    assert!(screen.contains("ab"));
+}
+
+#[test]
+fn tutorial_repeats_failed_drills() {
+    let mut events = keys(" xb ");
+    let (screen, _) = tutorial(events.clone());
+    assert!(screen.contains("Too many errors: 50.0%"));
+    // drills cannot be corrected
+    events.extend(keys(" a"));
+    events.push(Event::Key(KeyPress::Backspace));
+    events.extend(keys("b "));
+    let (screen, app) = tutorial(events.clone());
+    assert!(screen.contains("Again?"));
+    events.extend(keys("n"));
+    let (_, app_after) = tutorial(events);
+    assert!(!app.is_done());
+    assert!(app_after.is_done());
+}
+
+/// Drives a lesson map with no saved progress through `events`, returning
+/// what is on screen at the end.
+fn lesson_map(events: Vec<Event>) -> String {
+    let settings = Settings::default();
+    let mut state: std::boxed::Box<dyn states::State<TestBackend>> = std::boxed::Box::new(
+        states::LessonMap::new(Curriculum::default(), Progress::default(), settings),
+    );
+    for event in events {
+        state = state.handle_event(event);
+    }
+    let mut terminal = Terminal::new(TestBackend::new(60, 12)).unwrap();
+    terminal.draw(|f| state.ui(f)).unwrap();
+    let buffer = terminal.backend().buffer();
+    buffer.content.iter().map(|cell| cell.symbol.as_str()).collect()
+}
+
+#[test]
+fn lesson_map_shows_locked_lessons() {
+    let screen = lesson_map(vec![]);
+    assert!(screen.contains("> 1. Home row"));
+    assert!(screen.contains("locked"));
+    let screen = lesson_map(vec![Event::Key(KeyPress::Down), Event::Key(KeyPress::Enter)]);
+    assert!(screen.contains("> 2. Top row"));
+    assert!(screen.contains("That lesson is still locked"));
+}
+
+#[test]
+fn lesson_map_drills_the_chosen_lesson() {
+    let screen = lesson_map(vec![Event::Key(KeyPress::Enter)]);
+    assert!(screen.contains("Home row"));
+    assert!(!screen.contains("Lessons"));
+    let screen = lesson_map(vec![Event::Key(KeyPress::Enter), Event::Key(KeyPress::Esc)]);
+    assert!(screen.contains("Results"));
+}
+
+#[test]
+fn lessons_keep_every_session_for_the_history() {
+    let mut events = vec![Event::Key(KeyPress::Enter)];
+    events.extend(keys("x"));
+    events.extend([Event::Key(KeyPress::Esc), Event::Key(KeyPress::Char(' '))]);
+    let settings = Settings::default();
+    let map = states::LessonMap::new(Curriculum::default(), Progress::default(), settings);
+    let mut lessons: std::boxed::Box<dyn states::State<TestBackend>> = std::boxed::Box::new(map);
+    for event in events.iter().cloned().chain(events.clone()) {
+        lessons = lessons.handle_event(event);
+    }
+    assert_eq!(lessons.records().len(), 2);
+    assert!(lessons.records().iter().all(|(_, log)| log.is_some()));
+}
+
+#[test]
+fn tutorials_keep_every_exercise_for_the_history() {
+    let program = crate::typ::Program::parse("D:ab\nD:cd\n").unwrap();
+    let mut tutorial: std::boxed::Box<dyn states::State<TestBackend>> = std::boxed::Box::new(
+        states::Tutorial::new(crate::typ::Interpreter::new(program), Settings::default()),
+    );
+    for event in keys("ab cd ") {
+        tutorial = tutorial.handle_event(event);
+    }
+    let records = tutorial.records();
+    assert_eq!(records.len(), 2);
+    assert!(records.iter().all(|(record, _)| record.completed));
+}
+
+#[test]
+fn generated_sessions_never_run_out_of_text() {
+    use crate::generator::{Generator, Level, Model};
+    let model = Model::train(["The cat sat on the mat."], Level::Word, 1);
+    let app = App::generate(Generator::new(model, 1), Settings::default()).unwrap();
+    let (screen, _) = drive_app(app, TestBackend::new(40, 12), keys(&"x".repeat(500)));
+    assert!(screen.contains("cat"));
+    assert!(!screen.contains("Results"));
+    let mut events = keys(&"x".repeat(500));
+    events.push(Event::Key(KeyPress::Esc));
+    let model = Model::train(["The cat sat on the mat."], Level::Word, 1);
+    let app = App::generate(Generator::new(model, 1), Settings::default()).unwrap();
+    let (screen, _) = drive_app(app, TestBackend::new(40, 12), events);
+    assert!(screen.contains("Errors: 500"));
+}
+
+#[test]
+fn generated_sessions_log_all_the_text_generated() {
+    use crate::generator::{Generator, Level, Model};
+    let model = Model::train(["The cat sat on the mat."], Level::Word, 1);
+    let mut generator = Generator::new(model, 1);
+    let first = crate::text_model::Data {
+        text: generator.generate(1),
+        ..Default::default()
+    };
+    let mut typing = states::Typing::new(&first.to_xml(), Settings::default()).unwrap();
+    typing.set_generator(generator);
+    let mut state: std::boxed::Box<dyn states::State<TestBackend>> = std::boxed::Box::new(typing);
+    for event in keys(&"x".repeat(500)) {
+        state = state.handle_event(event);
+    }
+    state = state.handle_event(Event::Key(KeyPress::Esc));
+    let text = state.keystroke_log().unwrap().text_model().unwrap().text();
+    assert!(text.chars().count() > 500);
+}
+
+fn error(source: &str) -> AppError {
+    App::<TestBackend>::new(source, Settings::default())
+        .err()
+        .unwrap()
+}
+
+#[test]
+fn missing_files_are_named() {
+    let e = read_file("no/such/text.xml").unwrap_err();
+    assert!(matches!(e, AppError::MissingFile(_)));
+    assert!(e.to_string().contains("no/such/text.xml"));
+}
+
+#[test]
+fn bad_xml_is_its_own_error() {
+    let e = error("<klata_text><text>abc</txt></klata_text>");
+    assert!(matches!(e, AppError::BadText(_)));
+}
+
+#[test]
+fn bad_collections_are_bad_texts_too() {
+    let path = std::env::temp_dir().join(format!("klata-bad-{}.json", std::process::id()));
+    std::fs::write(&path, "[{\"text\": ").unwrap();
+    let e = read_text(path.to_str().unwrap(), &ImportOptions::default(), 0).unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+    assert!(matches!(e, AppError::BadText(_)));
+    assert_eq!(e.exit_code(), 4);
+}
+
+#[test]
+fn unsupported_characters_are_their_own_error() {
+    let e = error("<klata_text><text>caf\u{e9}</text></klata_text>");
+    assert!(matches!(e, AppError::UnsupportedCharacter(_)));
+    assert!(e.to_string().contains("U+00E9"));
+}
+
+#[test]
+fn text_errors_name_the_file() {
+    let e = error("<klata_text><text>caf\u{e9}</text></klata_text>").in_file("cafe.xml");
+    assert!(e.to_string().starts_with("cafe.xml:1:22: "));
+}
+
+#[test]
+fn errors_have_distinct_exit_codes() {
+    let errors = [
+        AppError::MissingFile("a.xml".into()),
+        error("<klata_text><text>abc</txt></klata_text>"),
+        error("<klata_text><text>caf\u{e9}</text></klata_text>"),
+        AppError::Terminal(std::io::ErrorKind::Other.into()),
+        AppError::Other("anything else".into()),
+    ];
+    let codes = errors
+        .iter()
+        .map(AppError::exit_code)
+        .collect::<std::collections::BTreeSet<_>>();
+    assert_eq!(codes.len(), errors.len());
+    assert!(!codes.contains(&0));
+}
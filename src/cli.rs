@@ -1,7 +1,341 @@
-use clap::Parser;
+#[cfg(test)]
+mod tests;
+
+use crate::difficulty::{Bounds, Index};
+use crate::generator::Level;
+use crate::layout::Layout;
+use crate::race::BotProfile;
+use crate::session::{GhostRun, Pacer, Rules, Settings, Start};
+use crate::text_model::{Correction, Fields, Format, ImportOptions};
+use clap::{Args, Parser, Subcommand};
+use std::time::Duration;
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
+#[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 pub struct Cli {
-    pub file: String,
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+    #[clap(flatten)]
+    pub session: SessionArgs,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Replay a recorded keystroke log
+    Replay {
+        log: String,
+        /// Playback speed, as a multiple of real time
+        #[clap(long, default_value_t = 1.0)]
+        speed: f64,
+    },
+    /// Check text files, or directories of them, for problems
+    Validate {
+        #[clap(required = true)]
+        paths: Vec<String>,
+        /// Report problems as JSON
+        #[clap(long)]
+        json: bool,
+    },
+    /// Type a keystroke script into a session without a terminal, and
+    /// print the results as JSON
+    Script {
+        #[clap(flatten)]
+        session: SessionArgs,
+        /// The keystroke script, or - to read it from stdin
+        #[clap(long, value_name = "SCRIPT", default_value = "-")]
+        keys: String,
+    },
+    /// Import texts from collections, such as JSON or CSV quote
+    /// datasets, into a klata library file
+    Import {
+        /// Files to import from, or - to read from stdin
+        #[clap(required = true)]
+        inputs: Vec<String>,
+        #[clap(flatten)]
+        input: InputArgs,
+        /// The library file to write, or - to write to stdout
+        #[clap(long, short, value_name = "FILE", default_value = "-")]
+        output: String,
+    },
+    /// Work through a GNU Typist (.typ) lesson script
+    Tutorial {
+        file: String,
+        /// Start from this label rather than the top of the script
+        #[clap(long)]
+        label: Option<String>,
+        /// What backspace may erase in speed tests; drills allow none
+        #[clap(long, default_value_t = Correction::Free, possible_values = Correction::NAMES)]
+        correction: Correction,
+        /// Show a keyboard in this layout, or layout file
+        #[clap(long, value_name = "LAYOUT")]
+        layout: Option<Layout>,
+        /// The layout your system types in, to practise --layout
+        #[clap(long, value_name = "LAYOUT", requires = "layout")]
+        os_layout: Option<Layout>,
+    },
+    /// Learn to type lesson by lesson, each unlocking the next
+    Lessons {
+        /// Whose progress to show and save
+        #[clap(long, default_value = "default")]
+        profile: String,
+        /// A curriculum file to use instead of the built-in one
+        #[clap(long, value_name = "FILE")]
+        curriculum: Option<String>,
+        /// Seed for the drills, to type the same ones again. A random seed
+        /// is printed to stderr, so that its drills can be had again
+        #[clap(long)]
+        seed: Option<u64>,
+        /// What backspace may erase
+        #[clap(long, default_value_t = Correction::Free, possible_values = Correction::NAMES)]
+        correction: Correction,
+        /// Show a keyboard in this layout, or layout file
+        #[clap(long, value_name = "LAYOUT")]
+        layout: Option<Layout>,
+        /// The layout your system types in, to practise --layout
+        #[clap(long, value_name = "LAYOUT", requires = "layout")]
+        os_layout: Option<Layout>,
+    },
+    /// Type endless text made up by a model of a library or corpus
+    Generate {
+        /// Libraries, collections or plain text files to learn from
+        #[clap(required = true)]
+        corpus: Vec<String>,
+        #[clap(flatten)]
+        input: InputArgs,
+        /// Whether the model strings together characters or words
+        #[clap(long, default_value_t = Level::Word, possible_values = Level::NAMES)]
+        level: Level,
+        /// How many characters or words of context each choice depends on
+        #[clap(long, default_value_t = 2)]
+        order: usize,
+        /// Only use these keys, as a lesson does; space is always allowed
+        #[clap(long, value_name = "KEYS")]
+        keys: Option<String>,
+        /// Seed for the model, to generate the same text again. A random
+        /// seed is printed to stderr, so that its text can be had again
+        #[clap(long)]
+        seed: Option<u64>,
+        /// Print this many characters of text, rather than typing it
+        #[clap(long, value_name = "CHARS")]
+        print: Option<usize>,
+        /// What backspace may erase
+        #[clap(long, default_value_t = Correction::Free, possible_values = Correction::NAMES)]
+        correction: Correction,
+        /// Show a keyboard in this layout, or layout file
+        #[clap(long, value_name = "LAYOUT")]
+        layout: Option<Layout>,
+        /// The layout your system types in, to practise --layout
+        #[clap(long, value_name = "LAYOUT", requires = "layout")]
+        os_layout: Option<Layout>,
+    },
+    /// Summarise past sessions, with how each finger has fared in
+    /// those typed with --layout
+    Stats,
+    /// Host a race over the network
+    Serve {
+        #[clap(flatten)]
+        session: SessionArgs,
+        /// Port to listen on
+        #[clap(long, default_value_t = 7878)]
+        port: u16,
+        /// Your name on the leaderboard
+        #[clap(long, default_value = "host")]
+        name: String,
+    },
+    /// Join a race hosted over the network
+    Join {
+        /// The host's address, as HOST:PORT
+        address: String,
+        /// Your name on the leaderboard
+        #[clap(long, default_value = "guest")]
+        name: String,
+        #[clap(flatten)]
+        typing: TypingArgs,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct SessionArgs {
+    /// The text to type, or - to read it from stdin. A file of several
+    /// texts gives one at random.
+    #[clap(required = true)]
+    pub file: Option<String>,
+    #[clap(flatten)]
+    pub input: InputArgs,
+    #[clap(flatten)]
+    pub typing: TypingArgs,
+}
+
+/// How a typing session is run, whatever its text.
+#[derive(Args, Debug)]
+pub struct TypingArgs {
+    /// Count down from this many seconds before the session starts,
+    /// rather than starting on the first keystroke
+    #[clap(
+        long,
+        value_name = "SECONDS",
+        min_values = 0,
+        max_values = 1,
+        require_equals = true,
+        default_missing_value = "3"
+    )]
+    pub countdown: Option<u64>,
+    /// Pause the session after this many seconds without input (0 disables)
+    #[clap(long, value_name = "SECONDS", default_value_t = 10)]
+    pub idle_timeout: u64,
+    /// Fail the session on the first wrong character
+    #[clap(long)]
+    pub sudden_death: bool,
+    /// Fail the session after more than this many errors
+    #[clap(long, value_name = "COUNT")]
+    pub max_errors: Option<u32>,
+    /// Fail the session if accuracy falls below this percentage
+    #[clap(long, value_name = "PERCENT")]
+    pub min_accuracy: Option<f64>,
+    /// Fail the session if speed falls below this many words per minute
+    #[clap(long, value_name = "WPM")]
+    pub min_wpm: Option<f64>,
+    /// What backspace may erase
+    #[clap(long, default_value_t = Correction::Free, possible_values = Correction::NAMES)]
+    pub correction: Correction,
+    /// Hide mistakes while typing and reveal them on the results screen
+    #[clap(long)]
+    pub blind: bool,
+    /// Save a log of every keystroke to this file, for replay
+    #[clap(long, value_name = "FILE")]
+    pub record: Option<String>,
+    /// Race a ghost of your best run through the text, or of the run
+    /// in the given keystroke log
+    #[clap(
+        long,
+        value_name = "LOG",
+        min_values = 0,
+        max_values = 1,
+        require_equals = true,
+        default_missing_value = ""
+    )]
+    pub ghost: Option<String>,
+    /// Show a pacer moving through the text at this many words per minute
+    #[clap(long, value_name = "WPM")]
+    pub pace: Option<f64>,
+    /// Race a simulated typist: one of novice, average, fast or pro, or
+    /// NAME:WPM[:ACCURACY[:BURSTINESS[:CORRECTION]]]. May be repeated.
+    #[clap(long = "bot", value_name = "PROFILE", multiple_occurrences = true)]
+    pub bots: Vec<BotProfile>,
+    /// Seed for the bots and for choosing a text from a library, to replay
+    /// the same race. A random seed is printed to stderr, so that its race
+    /// can be had again
+    #[clap(long)]
+    pub seed: Option<u64>,
+    /// Show a keyboard with the next key highlighted: one of qwerty,
+    /// dvorak, colemak, workman, azerty or qwertz, or a layout file
+    #[clap(long, value_name = "LAYOUT")]
+    pub layout: Option<Layout>,
+    /// The layout your system types in, to practise --layout without
+    /// switching to it: each key types what it would in --layout
+    #[clap(long, value_name = "LAYOUT", requires = "layout")]
+    pub os_layout: Option<Layout>,
+}
+
+/// How to read files of texts.
+#[derive(Args, Debug)]
+pub struct InputArgs {
+    /// Read texts as this format, rather than guessing from the file's
+    /// name and content
+    #[clap(long, possible_values = Format::NAMES)]
+    pub format: Option<Format>,
+    /// The field or column holding each text, in JSON and CSV
+    #[clap(long, value_name = "NAME", default_value = "text")]
+    pub text_field: String,
+    /// The field or column holding each author
+    #[clap(long, value_name = "NAME", default_value = "author")]
+    pub author_field: String,
+    /// The field or column holding where each text is from
+    #[clap(long, value_name = "NAME", default_value = "source")]
+    pub source_field: String,
+    /// The field or column holding each date, as YYYY-MM-DD
+    #[clap(long, value_name = "NAME", default_value = "date")]
+    pub date_field: String,
+    /// Leave out texts easier than this, where everyday prose scores 1
+    #[clap(long, value_name = "SCORE")]
+    pub min_difficulty: Option<f64>,
+    /// Leave out texts harder than this
+    #[clap(long, value_name = "SCORE")]
+    pub max_difficulty: Option<f64>,
+}
+
+impl InputArgs {
+    pub fn options(&self) -> ImportOptions {
+        ImportOptions {
+            format: self.format,
+            fields: Fields {
+                text: self.text_field.clone(),
+                author: self.author_field.clone(),
+                source: self.source_field.clone(),
+                date: self.date_field.clone(),
+            },
+            difficulty: Bounds {
+                min: self.min_difficulty,
+                max: self.max_difficulty,
+                layout: None,
+            },
+        }
+    }
+}
+
+impl SessionArgs {
+    /// How to read the text file, scoring difficulty on the layout shown.
+    pub fn options(&self) -> ImportOptions {
+        let mut options = self.input.options();
+        options.difficulty.layout = self.typing.layout.clone();
+        options
+    }
+
+    pub fn settings(&self) -> Settings {
+        self.typing.settings()
+    }
+}
+
+impl TypingArgs {
+    pub fn settings(&self) -> Settings {
+        Settings {
+            start: match self.countdown {
+                Some(seconds) => Start::Countdown(seconds),
+                None => Start::FirstKeystroke,
+            },
+            idle_timeout: match self.idle_timeout {
+                0 => None,
+                secs => Some(Duration::from_secs(secs)),
+            },
+            rules: Rules {
+                sudden_death: self.sudden_death,
+                max_errors: self.max_errors,
+                min_accuracy: self.min_accuracy.map(|percent| percent / 100.0),
+                min_wpm: self.min_wpm,
+            },
+            correction: self.correction,
+            blind: self.blind,
+            ghost: self.ghost.as_ref().map(|log| match log.as_str() {
+                "" => GhostRun::Best,
+                path => GhostRun::File(path.into()),
+            }),
+            pacer: self.pace.map(|wpm| Pacer { wpm }),
+            bots: self.bots.clone(),
+            seed: seed_or_random(self.seed),
+            layout: self.layout.clone(),
+            os_layout: self.os_layout.clone(),
+            difficulty_index: Index::user_path(),
+        }
+    }
+}
+
+/// The seed given, or else a random one, which is printed to stderr so
+/// that whatever it seeds can be had again with `--seed`.
+pub fn seed_or_random(seed: Option<u64>) -> u64 {
+    seed.unwrap_or_else(|| {
+        let seed = rand::random();
+        eprintln!("Seed: {}", seed);
+        seed
+    })
 }
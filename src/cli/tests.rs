@@ -0,0 +1,133 @@
+use super::*;
+
+fn settings(args: &[&str]) -> Settings {
+    Cli::try_parse_from(args).unwrap().session.settings()
+}
+
+#[test]
+fn starts_on_first_keystroke_by_default() {
+    assert_eq!(settings(&["klata", "a.xml"]).start, Start::FirstKeystroke);
+}
+
+#[test]
+fn countdown_defaults_to_three_seconds() {
+    assert_eq!(
+        settings(&["klata", "--countdown", "a.xml"]).start,
+        Start::Countdown(3)
+    );
+}
+
+#[test]
+fn countdown_takes_optional_seconds() {
+    assert_eq!(
+        settings(&["klata", "--countdown=5", "a.xml"]).start,
+        Start::Countdown(5)
+    );
+}
+
+#[test]
+fn min_accuracy_is_given_as_a_percentage() {
+    assert_eq!(
+        settings(&["klata", "--min-accuracy", "95", "a.xml"])
+            .rules
+            .min_accuracy,
+        Some(0.95)
+    );
+}
+
+#[test]
+fn replay_is_a_subcommand() {
+    let cli = Cli::try_parse_from(["klata", "replay", "log.json"]).unwrap();
+    assert!(matches!(cli.command, Some(Command::Replay { .. })));
+}
+
+#[test]
+fn import_maps_fields_by_name() {
+    let args = ["klata", "import", "q.json", "--text-field", "quote", "-o", "lib.xml"];
+    match Cli::try_parse_from(args).unwrap().command {
+        Some(Command::Import { input, output, .. }) => {
+            let options = input.options();
+            assert_eq!(options.fields.text, "quote");
+            assert_eq!(options.fields.author, "author");
+            assert_eq!(output, "lib.xml");
+        }
+        command => panic!("Not an import: {:?}", command),
+    }
+}
+
+#[test]
+fn file_is_required_without_a_subcommand() {
+    assert!(Cli::try_parse_from(["klata"]).is_err());
+}
+
+#[test]
+fn ghost_races_best_run_by_default() {
+    assert_eq!(
+        settings(&["klata", "--ghost", "a.xml"]).ghost,
+        Some(GhostRun::Best)
+    );
+    assert_eq!(
+        settings(&["klata", "--ghost=run.json", "a.xml"]).ghost,
+        Some(GhostRun::File("run.json".into()))
+    );
+}
+
+#[test]
+fn bots_may_be_repeated() {
+    let bots = settings(&["klata", "--bot", "pro", "--bot", "Ann:60", "a.xml"]).bots;
+    assert_eq!(bots.len(), 2);
+    assert_eq!(bots[0].name, "pro");
+    assert_eq!(bots[1].wpm, 60.0);
+}
+
+#[test]
+fn zero_idle_timeout_disables_idle_detection() {
+    assert_eq!(
+        settings(&["klata", "--idle-timeout", "0", "a.xml"]).idle_timeout,
+        None
+    );
+}
+
+#[test]
+fn os_layout_needs_a_layout_to_emulate() {
+    let args = ["klata", "--os-layout", "qwerty", "a.xml"];
+    assert!(Cli::try_parse_from(args).is_err());
+    let args = ["klata", "--layout", "colemak", "--os-layout", "qwerty"];
+    let settings = settings(&[&args[..], &["a.xml"]].concat());
+    assert_eq!(settings.layout.unwrap().name, "Colemak");
+    assert_eq!(settings.os_layout.unwrap().name, "QWERTY");
+}
+
+#[test]
+fn generate_emulates_layouts_too() {
+    let args = ["klata", "generate", "--layout", "colemak", "--os-layout", "qwerty", "c.txt"];
+    match Cli::try_parse_from(args).unwrap().command {
+        Some(Command::Generate { os_layout, .. }) => {
+            assert_eq!(os_layout.unwrap().name, "QWERTY");
+        }
+        command => panic!("Not a generate: {:?}", command),
+    }
+    let args = ["klata", "generate", "--os-layout", "qwerty", "c.txt"];
+    assert!(Cli::try_parse_from(args).is_err());
+}
+
+#[test]
+fn difficulty_is_scored_on_the_layout_shown() {
+    let args = ["klata", "--max-difficulty", "1.2", "--layout", "dvorak", "a.xml"];
+    let options = Cli::try_parse_from(args).unwrap().session.options();
+    assert_eq!(options.difficulty.max, Some(1.2));
+    assert_eq!(options.difficulty.layout.unwrap().name, "Dvorak");
+}
+
+#[test]
+fn join_takes_the_session_options() {
+    let args = ["klata", "join", "host:7878", "--layout", "dvorak", "--record", "r.log"];
+    match Cli::try_parse_from(args).unwrap().command {
+        Some(Command::Join { address, typing, .. }) => {
+            assert_eq!(address, "host:7878");
+            assert_eq!(typing.record.as_deref(), Some("r.log"));
+            assert_eq!(typing.settings().layout.unwrap().name, "Dvorak");
+        }
+        command => panic!("Not a join: {:?}", command),
+    }
+}
@@ -0,0 +1,90 @@
+use super::score::VERSION;
+use super::Difficulty;
+use crate::layout::Layout;
+use crate::text_model::text_id;
+use serde::{Deserialize, Serialize};
+use std::{
+    boxed::Box,
+    collections::HashMap,
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+/// The difficulty of every text scored so far, so that libraries need not
+/// be scored again each time a text is chosen from them.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Index {
+    /// Scores by scoring version, text id, and the layout they were scored
+    /// on if any.
+    scores: HashMap<String, Difficulty>,
+    #[serde(skip)]
+    changed: bool,
+}
+
+impl Index {
+    /// The index in the user's data directory, if there is one.
+    pub fn user_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("klata").join("index.json"))
+    }
+
+    /// The index saved at `path`, or an empty one if nothing is saved.
+    /// Scores from other versions of the scoring are left out.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Index, Box<dyn Error>> {
+        let mut index: Index = match std::fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Index::default(),
+            Err(e) => return Err(e.into()),
+        };
+        let count = index.scores.len();
+        let current = format!("v{}:", VERSION);
+        index.scores.retain(|key, _| key.starts_with(&current));
+        index.changed = index.scores.len() < count;
+        Ok(index)
+    }
+
+    /// The difficulty of `text` on `layout`, from the index at `path`, which
+    /// keeps it if it is new.
+    pub fn score_in<P: AsRef<Path>>(
+        path: P,
+        text: &str,
+        layout: Option<&Layout>,
+    ) -> Result<Difficulty, Box<dyn Error>> {
+        let mut index = Index::load(&path)?;
+        let difficulty = index.score(text, layout).clone();
+        index.save(&path)?;
+        Ok(difficulty)
+    }
+
+    /// Saves the index, if any text has been scored since it was loaded.
+    pub fn save<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Box<dyn Error>> {
+        if !self.changed {
+            return Ok(());
+        }
+        if let Some(dir) = path.as_ref().parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string(self)?)?;
+        self.changed = false;
+        Ok(())
+    }
+
+    /// The difficulty of `text` on `layout`, scored now if it has not
+    /// been already.
+    pub fn score(&mut self, text: &str, layout: Option<&Layout>) -> &Difficulty {
+        let key = match layout {
+            Some(layout) => format!(
+                "v{}:{}:{}",
+                VERSION,
+                text_id(text),
+                layout.name.to_lowercase()
+            ),
+            None => format!("v{}:{}", VERSION, text_id(text)),
+        };
+        if !self.scores.contains_key(&key) {
+            self.changed = true;
+        }
+        self.scores
+            .entry(key)
+            .or_insert_with(|| Difficulty::new(text, layout))
+    }
+}
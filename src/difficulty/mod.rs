@@ -0,0 +1,8 @@
+#[cfg(test)]
+mod tests;
+
+mod index;
+mod score;
+
+pub use index::Index;
+pub use score::{Bounds, Difficulty};
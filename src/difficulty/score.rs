@@ -0,0 +1,144 @@
+use super::Index;
+use crate::layout::{Finger, Layout, HOME_ROW, SPACE_ROW};
+use crate::text_model::Data;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// The version of the scoring, to be raised whenever the measures or their
+/// weights change, so that scores cached with the old ones are not used.
+pub const VERSION: u32 = 1;
+
+/// Words this long or longer count as long.
+const LONG_WORD: usize = 8;
+
+/// What each measure is like in everyday English prose, which scores 1,
+/// and how much the score rises for each unit above it.
+const WORD_LENGTH: (f64, f64) = (4.2, 0.15);
+const LONG_WORDS: (f64, f64) = (0.08, 0.5);
+const RARE_WORDS: (f64, f64) = (0.4, 0.4);
+const PUNCTUATION: (f64, f64) = (0.03, 3.0);
+const NUMBERS: (f64, f64) = (0.0, 3.0);
+const CAPITALS: (f64, f64) = (0.03, 2.0);
+const FINGER_TRAVEL: (f64, f64) = (0.7, 0.3);
+const SAME_FINGER: (f64, f64) = (0.06, 2.0);
+
+/// How hard a text is to type, judged from its content. Densities are
+/// per character, and shares per word or per pair of keys.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Difficulty {
+    pub words: usize,
+    pub mean_word_length: f64,
+    /// The share of words of eight letters or more.
+    pub long_words: f64,
+    /// The share of words outside the common words drilled in lessons.
+    pub rare_words: f64,
+    pub punctuation: f64,
+    pub numbers: f64,
+    pub capitals: f64,
+    /// How many rows each key is from the home row, on average, if the
+    /// text was scored with a layout.
+    pub finger_travel: Option<f64>,
+    /// The share of pairs of keys pressed by the same finger.
+    pub same_finger: Option<f64>,
+    /// The overall difficulty, where everyday prose scores about 1 and a
+    /// text that scores 1.2 is about a fifth harder.
+    pub score: f64,
+}
+
+impl Difficulty {
+    /// Scores `text`, taking fingering into account if there is a layout.
+    pub fn new(text: &str, layout: Option<&Layout>) -> Difficulty {
+        let common = crate::lessons::WORDS.lines().collect::<HashSet<_>>();
+        let words = text
+            .split_whitespace()
+            .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>();
+        let chars = text.chars().filter(|c| !c.is_whitespace()).count().max(1) as f64;
+        let share = |count: usize, of: usize| count as f64 / of.max(1) as f64;
+        let density = |f: fn(&char) -> bool| text.chars().filter(f).count() as f64 / chars;
+        let lengths = words.iter().map(|word| word.chars().count());
+        let mut difficulty = Difficulty {
+            words: words.len(),
+            mean_word_length: share(lengths.clone().sum(), words.len()),
+            long_words: share(lengths.filter(|&l| l >= LONG_WORD).count(), words.len()),
+            rare_words: share(
+                words
+                    .iter()
+                    .filter(|word| !common.contains(word.to_lowercase().as_str()))
+                    .count(),
+                words.len(),
+            ),
+            punctuation: density(char::is_ascii_punctuation),
+            numbers: density(char::is_ascii_digit),
+            capitals: density(char::is_ascii_uppercase),
+            ..Difficulty::default()
+        };
+        if let Some(layout) = layout {
+            difficulty.fingering(text, layout);
+        }
+        difficulty.score = difficulty.score();
+        difficulty
+    }
+
+    fn fingering(&mut self, text: &str, layout: &Layout) {
+        let keys = text
+            .chars()
+            .filter_map(|c| layout.locate(c))
+            .filter(|key| key.row < SPACE_ROW)
+            .collect::<Vec<_>>();
+        let travel: usize = keys.iter().map(|key| key.row.abs_diff(HOME_ROW)).sum();
+        self.finger_travel = Some(travel as f64 / keys.len().max(1) as f64);
+        let same_finger = keys
+            .windows(2)
+            .filter(|pair| {
+                let (a, b) = (pair[0], pair[1]);
+                a.finger == b.finger
+                    && a.finger != Finger::Thumb
+                    && (a.row, a.column) != (b.row, b.column)
+            })
+            .count();
+        self.same_finger = Some(same_finger as f64 / keys.len().saturating_sub(1).max(1) as f64);
+    }
+
+    fn score(&self) -> f64 {
+        let above = |value: f64, (typical, weight): (f64, f64)| (value - typical) * weight;
+        let mut score = 1.0
+            + above(self.mean_word_length, WORD_LENGTH)
+            + above(self.long_words, LONG_WORDS)
+            + above(self.rare_words, RARE_WORDS)
+            + above(self.punctuation, PUNCTUATION)
+            + above(self.numbers, NUMBERS)
+            + above(self.capitals, CAPITALS);
+        if let (Some(travel), Some(same_finger)) = (self.finger_travel, self.same_finger) {
+            score += above(travel, FINGER_TRAVEL) + above(same_finger, SAME_FINGER);
+        }
+        score.clamp(0.5, 3.0)
+    }
+}
+
+/// The difficulties a text may have to be chosen, and the layout to score
+/// it on.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Bounds {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub layout: Option<Layout>,
+}
+
+impl Bounds {
+    /// Whether there are any bounds at all.
+    pub fn is_set(&self) -> bool {
+        self.min.is_some() || self.max.is_some()
+    }
+
+    pub fn contains(&self, score: f64) -> bool {
+        self.min.is_none_or(|min| score >= min) && self.max.is_none_or(|max| score <= max)
+    }
+
+    /// Leaves out the texts outside the bounds, scoring them with `index`.
+    pub fn retain(&self, texts: &mut Vec<Data>, index: &mut Index) {
+        texts.retain(|data| self.contains(index.score(&data.text, self.layout.as_ref()).score));
+    }
+}
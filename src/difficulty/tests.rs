@@ -0,0 +1,83 @@
+use super::*;
+use crate::layout::Layout;
+use crate::text_model::Data;
+
+const PROSE: &str = "Call me Ishmael. Some years ago, never mind how long precisely, \
+    having little or no money in my purse, and nothing particular to interest me on shore, \
+    I thought I would sail about a little and see the watery part of the world.";
+
+#[test]
+fn everyday_prose_scores_about_one() {
+    let difficulty = Difficulty::new(PROSE, None);
+    assert_eq!(difficulty.words, 43);
+    assert!((0.9..1.1).contains(&difficulty.score));
+    let easy = Difficulty::new("The man and the boy came home after a long day.", None);
+    assert!(easy.score < 0.9);
+    assert_eq!(difficulty.finger_travel, None);
+}
+
+#[test]
+fn long_rare_words_numbers_and_symbols_are_harder() {
+    let hard = "Quantum chromodynamics (QCD) predicts 1,337 gluon-mediated interactions!";
+    let difficulty = Difficulty::new(hard, None);
+    assert!(difficulty.long_words > 0.3);
+    assert_eq!(difficulty.rare_words, 1.0);
+    assert!(difficulty.numbers > 0.0);
+    assert!(difficulty.score > Difficulty::new(PROSE, None).score + 0.5);
+}
+
+#[test]
+fn layouts_score_finger_travel_and_same_finger_bigrams() {
+    let qwerty = Layout::built_in("qwerty").unwrap();
+    let home = Difficulty::new("as dad asks", Some(&qwerty));
+    assert_eq!(home.finger_travel, Some(0.0));
+    let reaching = Difficulty::new("deck my minx", Some(&qwerty));
+    assert!(reaching.finger_travel.unwrap() > 0.5);
+    assert!(reaching.same_finger.unwrap() > 0.0);
+    assert!(reaching.score > home.score);
+}
+
+#[test]
+fn bounds_leave_out_texts_and_index_their_scores() {
+    let text = |text: &str| Data {
+        text: text.to_string(),
+        ..Data::default()
+    };
+    let mut texts = vec![text(PROSE), text("Zygomorphic 42% xylophones!")];
+    let bounds = Bounds {
+        max: Some(1.5),
+        ..Bounds::default()
+    };
+    assert!(bounds.is_set());
+    let mut index = Index::default();
+    bounds.retain(&mut texts, &mut index);
+    assert_eq!(texts, [text(PROSE)]);
+
+    let path = std::env::temp_dir().join(format!("klata-index-{}.json", std::process::id()));
+    index.save(&path).unwrap();
+    let mut loaded = Index::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.score(PROSE, None), index.score(PROSE, None));
+    assert!(Index::load(&path).unwrap().score(PROSE, None).words > 0);
+}
+
+#[test]
+fn scores_from_other_versions_are_not_used() {
+    let path = std::env::temp_dir().join(format!("klata-old-index-{}.json", std::process::id()));
+    let stale = Difficulty {
+        score: 3.0,
+        ..Difficulty::default()
+    };
+    let old = format!(
+        r#"{{"scores": {{"{}": {}}}}}"#,
+        crate::text_model::text_id(PROSE),
+        serde_json::to_string(&stale).unwrap()
+    );
+    std::fs::write(&path, old).unwrap();
+    let scored = Index::score_in(&path, PROSE, None).unwrap();
+    let saved = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(scored, Difficulty::new(PROSE, None));
+    assert!(saved.contains(&format!("\"v1:{}\"", crate::text_model::text_id(PROSE))));
+    assert!(!saved.contains(&format!("\"{}\"", crate::text_model::text_id(PROSE))));
+}
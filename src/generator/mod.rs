@@ -0,0 +1,6 @@
+#[cfg(test)]
+mod tests;
+
+mod model;
+
+pub use model::{Generator, Level, Model};
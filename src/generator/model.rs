@@ -0,0 +1,199 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::collections::{HashMap, HashSet};
+
+/// What a model treats as one token of text.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Level {
+    /// Characters, which makes up words as well as sentences.
+    Char,
+    /// Whole words, with the punctuation attached to them.
+    Word,
+}
+
+impl Level {
+    pub const NAMES: [&'static str; 2] = ["char", "word"];
+}
+
+impl std::fmt::Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            Level::Char => Level::NAMES[0],
+            Level::Word => Level::NAMES[1],
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl std::str::FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Level, String> {
+        match s {
+            "char" => Ok(Level::Char),
+            "word" => Ok(Level::Word),
+            _ => Err(format!("Unknown level '{}'", s)),
+        }
+    }
+}
+
+/// An n-gram model of some texts: for each run of `order` tokens, the
+/// tokens that follow it and how often.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Model {
+    level: Level,
+    order: usize,
+    next: HashMap<Vec<String>, Vec<(String, u32)>>,
+    /// Runs that begin a text or a sentence, to start from. Kept in the
+    /// order they were found, so that a seed always gives the same text.
+    starts: Vec<Vec<String>>,
+}
+
+impl Model {
+    /// Learns which tokens follow which in `texts`, over runs of `order`
+    /// tokens, which must be at least one.
+    pub fn train<'a, I>(texts: I, level: Level, order: usize) -> Model
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let order = order.max(1);
+        let mut model = Model {
+            level,
+            order,
+            next: HashMap::new(),
+            starts: Vec::new(),
+        };
+        let mut starts = HashSet::new();
+        for text in texts {
+            let tokens = tokens(text, level);
+            for (i, window) in tokens.windows(order + 1).enumerate() {
+                let context = window[..order].to_vec();
+                let begins = match (i, level) {
+                    (0, _) => true,
+                    (_, Level::Word) => tokens[i - 1].ends_with(['.', '!', '?']),
+                    (_, Level::Char) => tokens[i - 1] == " ",
+                };
+                if begins && starts.insert(context.clone()) {
+                    model.starts.push(context.clone());
+                }
+                let followers = model.next.entry(context).or_default();
+                match followers
+                    .iter_mut()
+                    .find(|(token, _)| *token == window[order])
+                {
+                    Some((_, count)) => *count += 1,
+                    None => followers.push((window[order].clone(), 1)),
+                }
+            }
+        }
+        model
+    }
+
+    /// Leaves out every token with a character outside `keys`, as for a
+    /// lesson that has only taught those keys. Spaces are always allowed.
+    pub fn restrict(&mut self, keys: &str) {
+        let allowed = |token: &String| token.chars().all(|c| c == ' ' || keys.contains(c));
+        self.next.retain(|context, followers| {
+            followers.retain(|(token, _)| allowed(token));
+            context.iter().all(allowed) && !followers.is_empty()
+        });
+        let next = &self.next;
+        self.starts.retain(|start| next.contains_key(start));
+    }
+
+    /// Whether the model has nothing to generate text from.
+    pub fn is_empty(&self) -> bool {
+        self.starts.is_empty()
+    }
+}
+
+/// The tokens of `text`, with runs of whitespace made single spaces.
+fn tokens(text: &str, level: Level) -> Vec<String> {
+    let words = text.split_whitespace();
+    match level {
+        Level::Word => words.map(str::to_string).collect(),
+        Level::Char => words
+            .collect::<Vec<_>>()
+            .join(" ")
+            .chars()
+            .map(String::from)
+            .collect(),
+    }
+}
+
+/// Endless text from a model, the same every time for the same seed.
+pub struct Generator {
+    model: Model,
+    rng: ChaCha8Rng,
+    context: Vec<String>,
+    /// The last character generated, to know where spaces are needed.
+    last: Option<char>,
+}
+
+impl Generator {
+    /// A generator for `model`, which must not be empty.
+    pub fn new(model: Model, seed: u64) -> Generator {
+        assert!(!model.is_empty(), "a generator needs a model to draw from");
+        Generator {
+            model,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            context: Vec::new(),
+            last: None,
+        }
+    }
+
+    /// At least `chars` more characters of text, carrying on from the last.
+    pub fn generate(&mut self, chars: usize) -> String {
+        let mut text = String::new();
+        // counted as it grows, as counting the text each time is quadratic
+        let mut length = 0;
+        while length < chars {
+            let tokens = match self.follower() {
+                Some(token) => {
+                    self.context.remove(0);
+                    self.context.push(token.clone());
+                    vec![token]
+                }
+                None => {
+                    // a dead end, or the very start, so begin afresh
+                    let start =
+                        self.model.starts[self.rng.gen_range(0..self.model.starts.len())].clone();
+                    if self.last.is_some_and(|c| c != ' ') {
+                        length += self.push(&mut text, " ");
+                    }
+                    self.context = start.clone();
+                    start
+                }
+            };
+            for token in &tokens {
+                if self.model.level == Level::Word && self.last.is_some_and(|c| c != ' ') {
+                    length += self.push(&mut text, " ");
+                }
+                length += self.push(&mut text, token);
+            }
+        }
+        text
+    }
+
+    /// Adds `s` to `text`, returning how many characters it has.
+    fn push(&mut self, text: &mut String, s: &str) -> usize {
+        text.push_str(s);
+        self.last = s.chars().last().or(self.last);
+        s.chars().count()
+    }
+
+    /// A token to follow the context, chosen as often as it followed it in
+    /// the texts the model learnt from.
+    fn follower(&mut self) -> Option<String> {
+        let followers = self.model.next.get(&self.context)?;
+        let total = followers.iter().map(|(_, count)| count).sum::<u32>();
+        let mut pick = self.rng.gen_range(0..total);
+        for (token, count) in followers {
+            if pick < *count {
+                return Some(token.clone());
+            }
+            pick -= count;
+        }
+        None
+    }
+}
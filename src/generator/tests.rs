@@ -0,0 +1,68 @@
+use super::*;
+
+const CORPUS: [&str; 2] = [
+    "The cat sat on the mat. The dog sat on the log. A cat ran to the dog.",
+    "The dog ran to the mat, and the cat sat still.",
+];
+
+fn model(level: Level, order: usize) -> Model {
+    Model::train(CORPUS, level, order)
+}
+
+#[test]
+fn levels_are_named() {
+    for name in Level::NAMES {
+        assert_eq!(name.parse::<Level>().unwrap().to_string(), name);
+    }
+    assert!("phrase".parse::<Level>().is_err());
+}
+
+#[test]
+fn word_models_only_follow_words_seen_together() {
+    let mut generator = Generator::new(model(Level::Word, 1), 7);
+    let text = generator.generate(500);
+    assert!(text.chars().count() >= 500);
+    let words = text.split(' ').collect::<Vec<_>>();
+    assert!(words.iter().all(|word| !word.is_empty()));
+    let seen = CORPUS.join(" ");
+    for pair in words.windows(2) {
+        // pairs not seen together only where a text ran out and a
+        // sentence starts afresh
+        let together = format!("{} {}", pair[0], pair[1]);
+        assert!(seen.contains(&together) || ["The", "A"].contains(&pair[1]));
+    }
+}
+
+#[test]
+fn generators_with_the_same_seed_agree() {
+    let text = |seed| Generator::new(model(Level::Char, 3), seed).generate(200);
+    assert_eq!(text(1), text(1));
+    assert_ne!(text(1), text(2));
+}
+
+#[test]
+fn generated_text_carries_on_from_the_last() {
+    let mut generator = Generator::new(model(Level::Word, 2), 3);
+    let first = generator.generate(20);
+    let next = generator.generate(20);
+    assert!(!first.starts_with(' '));
+    assert!(next.starts_with(' '));
+    assert!(!format!("{}{}", first, next).contains("  "));
+}
+
+#[test]
+fn restricted_models_only_use_the_keys_given() {
+    let mut model = model(Level::Char, 2);
+    model.restrict("thecasd");
+    let text = Generator::new(model, 5).generate(300);
+    assert!(text.chars().all(|c| c == ' ' || "thecasd".contains(c)));
+    let mut model = self::model(Level::Word, 1);
+    model.restrict("xyz");
+    assert!(model.is_empty());
+}
+
+#[test]
+fn long_texts_are_generated_in_linear_time() {
+    let text = Generator::new(model(Level::Word, 2), 9).generate(1_000_000);
+    assert!(text.chars().count() >= 1_000_000);
+}
@@ -0,0 +1,66 @@
+use crate::session::KeystrokeLog;
+use std::{
+    boxed::Box,
+    error::Error,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// Keystroke logs of past sessions, stored in a directory per text.
+pub struct Logs {
+    dir: PathBuf,
+}
+
+impl Logs {
+    pub fn new<P: AsRef<Path>>(dir: P) -> Logs {
+        Logs {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// The logs in the user's data directory, if there is one.
+    pub fn user() -> Option<Logs> {
+        dirs::data_dir().map(|dir| Logs::new(dir.join("klata").join("logs")))
+    }
+
+    pub fn save(&self, log: &KeystrokeLog) -> Result<PathBuf, Box<dyn Error>> {
+        let dir = self.dir.join(log.text_model()?.id());
+        std::fs::create_dir_all(&dir)?;
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        let path = dir.join(format!("{}.json", timestamp));
+        log.save(&path)?;
+        Ok(path)
+    }
+
+    /// The logs recorded against the text, skipping any that fail to load.
+    pub fn for_text(&self, text_id: &str) -> Result<Vec<KeystrokeLog>, Box<dyn Error>> {
+        let dir = self.dir.join(text_id);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut paths = std::fs::read_dir(dir)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        paths.sort();
+        Ok(paths
+            .iter()
+            .filter_map(|path| KeystrokeLog::load(path).ok())
+            .collect())
+    }
+
+    /// The fastest completed run through the text, if there is one.
+    pub fn best(&self, text_id: &str) -> Result<Option<KeystrokeLog>, Box<dyn Error>> {
+        let mut best: Option<(Duration, KeystrokeLog)> = None;
+        for log in self.for_text(text_id)? {
+            if let Ok(Some(time)) = log.finish_time() {
+                if best.as_ref().is_none_or(|(best_time, _)| time < *best_time) {
+                    best = Some((time, log));
+                }
+            }
+        }
+        Ok(best.map(|(_, log)| log))
+    }
+}
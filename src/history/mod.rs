@@ -0,0 +1,12 @@
+#[cfg(test)]
+mod tests;
+
+mod logs;
+mod record;
+mod report;
+mod store;
+
+pub use logs::Logs;
+pub use record::Record;
+pub use report::report;
+pub use store::History;
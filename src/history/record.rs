@@ -0,0 +1,49 @@
+use crate::session::{Analysis, Failure, Stats};
+use crate::text_model::{Correction, TextModel};
+use serde::{Deserialize, Serialize};
+
+/// A single finished session as stored in the history file.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Record {
+    /// Seconds since the unix epoch at which the session ended.
+    pub timestamp: u64,
+    pub author: Option<String>,
+    pub completed: bool,
+    /// Why the session failed, if it broke one of the rules.
+    pub failure: Option<String>,
+    /// The backspace policy in force, so records can be compared like with like.
+    pub correction: Correction,
+    pub wpm: f64,
+    pub accuracy: f64,
+    pub errors: u32,
+    pub elapsed_secs: f64,
+    /// Time spent paused or idle, which is excluded from `elapsed_secs`.
+    pub idle_secs: f64,
+    /// How each finger fared, if the session was typed with a layout.
+    pub analysis: Option<Analysis>,
+    /// How hard the text was, to compare speeds across texts.
+    pub difficulty: Option<f64>,
+}
+
+impl Record {
+    pub fn new(stats: &Stats, text: &TextModel, failure: Option<&Failure>) -> Record {
+        Record {
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            author: text.author().map(str::to_string),
+            completed: stats.completed,
+            failure: failure.map(Failure::to_string),
+            correction: text.correction(),
+            wpm: stats.wpm(),
+            accuracy: stats.accuracy(),
+            errors: stats.errors,
+            elapsed_secs: stats.elapsed.as_secs_f64(),
+            idle_secs: stats.idle.as_secs_f64(),
+            analysis: None,
+            difficulty: None,
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use super::Record;
+use crate::layout::Finger;
+use crate::session::Analysis;
+
+/// A summary of past sessions, with how each finger has fared in those
+/// typed with a layout.
+pub fn report(records: &[Record]) -> String {
+    if records.is_empty() {
+        return "No sessions yet".to_string();
+    }
+    let count = records.len() as f64;
+    let completed = records.iter().filter(|r| r.completed).count();
+    let best = records.iter().map(|r| r.wpm).fold(0.0, f64::max);
+    let mut lines = vec![
+        format!("Sessions: {} ({} completed)", records.len(), completed),
+        format!(
+            "WPM: {:.1} average, {:.1} best",
+            records.iter().map(|r| r.wpm).sum::<f64>() / count,
+            best
+        ),
+        format!(
+            "Accuracy: {:.1}% average",
+            records.iter().map(|r| r.accuracy).sum::<f64>() / count * 100.0
+        ),
+    ];
+    // speeds on hard texts count for more, and on easy ones for less
+    let adjusted = records
+        .iter()
+        .filter_map(|r| Some(r.wpm * r.difficulty?))
+        .collect::<Vec<_>>();
+    if !adjusted.is_empty() {
+        lines.push(format!(
+            "Adjusted WPM: {:.1} average, for texts of difficulty 1",
+            adjusted.iter().sum::<f64>() / adjusted.len() as f64
+        ));
+    }
+    let mut analysis = Analysis::default();
+    let analysed = records.iter().filter_map(|r| r.analysis.as_ref());
+    analysed.for_each(|a| analysis.add(a));
+    if analysis.fingers.is_empty() {
+        return lines.join("\n");
+    }
+    lines.push(String::new());
+    lines.push(format!(
+        "{:<13} {:>6} {:>6} {:>8}",
+        "Finger", "Keys", "Errors", "Latency"
+    ));
+    for finger in Finger::ALL {
+        let stats = match analysis.fingers.get(&finger) {
+            Some(stats) => stats,
+            None => continue,
+        };
+        let latency = stats
+            .latency()
+            .map_or("-".to_string(), |ms| format!("{:.0} ms", ms));
+        lines.push(format!(
+            "{:<13} {:>6} {:>6} {:>8}",
+            finger.to_string(),
+            stats.presses,
+            stats.errors,
+            latency
+        ));
+    }
+    lines.push(String::new());
+    lines.extend(analysis.summary());
+    lines.join("\n")
+}
@@ -0,0 +1,62 @@
+use super::Record;
+use std::{
+    boxed::Box,
+    error::Error,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// Session records, stored one JSON object per line.
+pub struct History {
+    path: PathBuf,
+}
+
+/// The records read back from a history, and how many lines of it could
+/// not be read.
+#[derive(Debug, Default, PartialEq)]
+pub struct Records {
+    pub records: Vec<Record>,
+    pub skipped: usize,
+}
+
+impl History {
+    pub fn new<P: AsRef<Path>>(path: P) -> History {
+        History {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// The history file in the user's data directory, if there is one.
+    pub fn user() -> Option<History> {
+        dirs::data_dir().map(|dir| History::new(dir.join("klata").join("history.jsonl")))
+    }
+
+    pub fn append(&self, record: &Record) -> Result<(), Box<dyn Error>> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(record)?)?;
+        Ok(())
+    }
+    /// Every record in the history, oldest first, skipping lines that
+    /// aren't records. There are none if the file does not exist yet.
+    pub fn records(&self) -> Result<Records, Box<dyn Error>> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Records::default()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut records = Records::default();
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            match serde_json::from_str(line) {
+                Ok(record) => records.records.push(record),
+                Err(_) => records.skipped += 1,
+            }
+        }
+        Ok(records)
+    }
+}
@@ -0,0 +1,122 @@
+use super::*;
+use crate::session::{Key, KeystrokeLog};
+use crate::text_model::Correction;
+use std::time::Duration;
+
+const SOURCE: &str = "<klata_text><text>ab</text></klata_text>";
+
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("klata-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn run(keys: &str, seconds: u64) -> KeystrokeLog {
+    let mut log = KeystrokeLog::new(SOURCE, Correction::Free);
+    let mut text = log.text_model().unwrap();
+    keys.chars()
+        .for_each(|c| log.press(Key::Char(c), &mut text, Duration::from_secs(seconds)));
+    log
+}
+
+#[test]
+fn history_appends_records() {
+    let dir = temp_dir("history");
+    let history = History::new(dir.join("history.jsonl"));
+    history.append(&Record::default()).unwrap();
+    history.append(&Record::default()).unwrap();
+    let content = std::fs::read_to_string(dir.join("history.jsonl")).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(content.lines().count(), 2);
+}
+
+#[test]
+fn best_log_is_fastest_completed_run() {
+    let dir = temp_dir("logs");
+    let logs = Logs::new(&dir);
+    let text_id = run("", 0).text_model().unwrap().id();
+    logs.save(&run("ab", 5)).unwrap();
+    std::thread::sleep(Duration::from_millis(2));
+    logs.save(&run("a", 1)).unwrap();
+    std::thread::sleep(Duration::from_millis(2));
+    logs.save(&run("ab", 3)).unwrap();
+    let all = logs.for_text(&text_id).unwrap();
+    let best = logs.best(&text_id).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(all.len(), 3);
+    assert_eq!(best, Some(run("ab", 3)));
+}
+
+#[test]
+fn corrupt_logs_are_skipped() {
+    let dir = temp_dir("corrupt-logs");
+    let logs = Logs::new(&dir);
+    let text_id = run("", 0).text_model().unwrap().id();
+    logs.save(&run("ab", 3)).unwrap();
+    std::fs::write(dir.join(&text_id).join("0.json"), "{ not a log").unwrap();
+    let all = logs.for_text(&text_id).unwrap();
+    let best = logs.best(&text_id).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(all.len(), 1);
+    assert_eq!(best, Some(run("ab", 3)));
+}
+
+#[test]
+fn no_logs_for_unknown_text() {
+    let logs = Logs::new(temp_dir("no-logs"));
+    assert_eq!(logs.best("0000").unwrap(), None);
+}
+
+#[test]
+fn history_skips_lines_that_are_not_records() {
+    let dir = temp_dir("bad-records");
+    let history = History::new(dir.join("history.jsonl"));
+    history.append(&Record::default()).unwrap();
+    let mut content = std::fs::read_to_string(dir.join("history.jsonl")).unwrap();
+    content += "{ not a record\n";
+    std::fs::write(dir.join("history.jsonl"), content).unwrap();
+    history.append(&Record::default()).unwrap();
+    let records = history.records().unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(records.records.len(), 2);
+    assert_eq!(records.skipped, 1);
+}
+
+#[test]
+fn history_reads_back_its_records() {
+    let dir = temp_dir("records");
+    let history = History::new(dir.join("history.jsonl"));
+    assert_eq!(history.records().unwrap(), store::Records::default());
+    let record = Record {
+        wpm: 42.0,
+        completed: true,
+        difficulty: Some(1.5),
+        ..Record::default()
+    };
+    history.append(&record).unwrap();
+    history.append(&Record::default()).unwrap();
+    let records = history.records().unwrap().records;
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(records, [record, Record::default()]);
+    let report = report(&records);
+    assert!(report.contains("Sessions: 2 (1 completed)"));
+    assert!(report.contains("21.0 average, 42.0 best"));
+    // only sessions with a difficulty are adjusted
+    assert!(report.contains("Adjusted WPM: 63.0 average"));
+    assert!(!report.contains("Finger"));
+}
+
+#[test]
+fn report_shows_fingers_from_analysed_sessions() {
+    let layout = crate::layout::Layout::built_in("qwerty").unwrap();
+    let log = run("ab", 1);
+    let analysis = crate::session::Analysis::new(&log.text_model().unwrap(), &log, &layout);
+    let record = Record {
+        analysis: Some(analysis),
+        ..Record::default()
+    };
+    let report = report(&[record.clone(), record]);
+    assert!(report.contains("left pinky         2      0"));
+    assert!(report.contains("left index         2      0     0 ms"));
+    assert!(report.contains("Hands: 100% left, 0% right"));
+}
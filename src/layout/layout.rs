@@ -0,0 +1,396 @@
+use serde::{Deserialize, Serialize};
+use std::{boxed::Box, error::Error, path::Path};
+
+/// The layouts klata knows by name.
+const BUILT_IN: [(&str, &str); 6] = [
+    ("qwerty", include_str!("layouts/qwerty.json")),
+    ("dvorak", include_str!("layouts/dvorak.json")),
+    ("colemak", include_str!("layouts/colemak.json")),
+    ("workman", include_str!("layouts/workman.json")),
+    ("azerty", include_str!("layouts/azerty.json")),
+    ("qwertz", include_str!("layouts/qwertz.json")),
+];
+
+/// The fingers of touch typing, each with its own keys. Thumbs share the
+/// space bar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Finger {
+    LeftPinky,
+    LeftRing,
+    LeftMiddle,
+    LeftIndex,
+    Thumb,
+    RightIndex,
+    RightMiddle,
+    RightRing,
+    RightPinky,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+impl Finger {
+    pub const ALL: [Finger; 9] = [
+        Finger::LeftPinky,
+        Finger::LeftRing,
+        Finger::LeftMiddle,
+        Finger::LeftIndex,
+        Finger::Thumb,
+        Finger::RightIndex,
+        Finger::RightMiddle,
+        Finger::RightRing,
+        Finger::RightPinky,
+    ];
+
+    /// The hand the finger is on, or `None` for the thumbs.
+    pub fn hand(self) -> Option<Hand> {
+        match self {
+            Finger::Thumb => None,
+            finger if finger < Finger::Thumb => Some(Hand::Left),
+            _ => Some(Hand::Right),
+        }
+    }
+}
+
+impl std::fmt::Display for Finger {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            Finger::LeftPinky => "left pinky",
+            Finger::LeftRing => "left ring",
+            Finger::LeftMiddle => "left middle",
+            Finger::LeftIndex => "left index",
+            Finger::Thumb => "thumb",
+            Finger::RightIndex => "right index",
+            Finger::RightMiddle => "right middle",
+            Finger::RightRing => "right ring",
+            Finger::RightPinky => "right pinky",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The rows of physical keys, from the number row down, and the space bar.
+pub const ROWS: usize = 5;
+/// The row of the space bar.
+pub const SPACE_ROW: usize = 4;
+/// The row of the enter key, at its end.
+pub const HOME_ROW: usize = 2;
+
+/// Which finger presses each key on a standard keyboard, by row and by
+/// column from the leftmost key of the row. ISO keyboards have an extra
+/// key, for the left pinky, before the bottom row.
+const FINGERS: [&[Finger]; 4] = {
+    use Finger::*;
+    [
+        &[
+            LeftPinky,
+            LeftPinky,
+            LeftRing,
+            LeftMiddle,
+            LeftIndex,
+            LeftIndex,
+            RightIndex,
+            RightIndex,
+            RightMiddle,
+            RightRing,
+            RightPinky,
+            RightPinky,
+            RightPinky,
+        ],
+        &[
+            LeftPinky,
+            LeftRing,
+            LeftMiddle,
+            LeftIndex,
+            LeftIndex,
+            RightIndex,
+            RightIndex,
+            RightMiddle,
+            RightRing,
+            RightPinky,
+            RightPinky,
+            RightPinky,
+            RightPinky,
+        ],
+        &[
+            LeftPinky,
+            LeftRing,
+            LeftMiddle,
+            LeftIndex,
+            LeftIndex,
+            RightIndex,
+            RightIndex,
+            RightMiddle,
+            RightRing,
+            RightPinky,
+            RightPinky,
+            RightPinky,
+        ],
+        &[
+            LeftPinky,
+            LeftRing,
+            LeftMiddle,
+            LeftIndex,
+            LeftIndex,
+            RightIndex,
+            RightIndex,
+            RightMiddle,
+            RightRing,
+            RightPinky,
+            RightPinky,
+        ],
+    ]
+};
+
+/// A physical key and what it types.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Key {
+    pub unshifted: Option<char>,
+    pub shifted: Option<char>,
+    /// What the key types with AltGr held.
+    pub altgr: Option<char>,
+    pub finger: Finger,
+}
+
+impl Key {
+    /// What the key types with shift and AltGr held or not.
+    fn level(&self, shift: bool, altgr: bool) -> Option<char> {
+        match (shift, altgr) {
+            (_, true) => self.altgr,
+            (true, false) => self.shifted,
+            (false, false) => self.unshifted,
+        }
+    }
+}
+
+/// Where a character is typed: the key's row and column, whether shift
+/// or AltGr is held, and the finger that presses it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeyPosition {
+    pub row: usize,
+    pub column: usize,
+    pub shift: bool,
+    pub altgr: bool,
+    pub finger: Finger,
+}
+
+impl KeyPosition {
+    /// The finger that holds shift for the key: the other hand's pinky.
+    pub fn shift_finger(&self) -> Option<Finger> {
+        match (self.shift, self.finger.hand()) {
+            (false, _) => None,
+            (true, Some(Hand::Left)) => Some(Finger::RightPinky),
+            (true, _) => Some(Finger::LeftPinky),
+        }
+    }
+}
+
+/// A keyboard layout: which characters each physical key types, and the
+/// finger that presses it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layout {
+    pub name: String,
+    /// Whether the keyboard has the ISO key left of the bottom row.
+    pub iso: bool,
+    /// The keys of each row, from the number row to the bottom row.
+    pub rows: Vec<Vec<Key>>,
+}
+
+/// A layout as written in a definition file. Each row lists what its keys
+/// type, with a space for a key that types nothing; `shifted` is the same
+/// with shift held, and the optional `altgr` with AltGr held. `fingers`
+/// optionally overrides the standard fingering with a digit per key, from
+/// 0 for the left pinky to 8 for the right.
+#[derive(Deserialize)]
+struct Definition {
+    name: String,
+    #[serde(default)]
+    iso: bool,
+    rows: Vec<String>,
+    shifted: Vec<String>,
+    altgr: Option<Vec<String>>,
+    fingers: Option<Vec<String>>,
+}
+
+impl Layout {
+    /// The built-in layout called `name`, ignoring case.
+    pub fn built_in(name: &str) -> Option<Layout> {
+        BUILT_IN
+            .iter()
+            .find(|(built_in, _)| built_in.eq_ignore_ascii_case(name))
+            .map(|(_, definition)| Layout::parse(definition).expect("built-in layouts are valid"))
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Layout, Box<dyn Error>> {
+        Layout::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// A layout from the JSON of a definition file.
+    pub fn parse(json: &str) -> Result<Layout, Box<dyn Error>> {
+        let definition: Definition = serde_json::from_str(json)?;
+        if definition.rows.len() != 4 || definition.shifted.len() != 4 {
+            return Err("A layout needs four rows, and four shifted rows".into());
+        }
+        if definition.altgr.as_ref().is_some_and(|altgr| altgr.len() != 4) {
+            return Err("A layout with AltGr needs four AltGr rows".into());
+        }
+        let mut rows = Vec::new();
+        for (i, (row, shifted)) in definition.rows.iter().zip(&definition.shifted).enumerate() {
+            let (row, shifted) = (
+                row.chars().collect::<Vec<_>>(),
+                shifted.chars().collect::<Vec<_>>(),
+            );
+            if row.len() != shifted.len() {
+                return Err(format!(
+                    "Row {} has {} keys but {} shifted",
+                    i + 1,
+                    row.len(),
+                    shifted.len()
+                )
+                .into());
+            }
+            let altgr = match &definition.altgr {
+                Some(altgr) => altgr[i].chars().collect::<Vec<_>>(),
+                None => vec![' '; row.len()],
+            };
+            if altgr.len() != row.len() {
+                return Err(format!(
+                    "Row {} has {} keys but {} with AltGr",
+                    i + 1,
+                    row.len(),
+                    altgr.len()
+                )
+                .into());
+            }
+            let fingers = match &definition.fingers {
+                Some(fingers) => {
+                    custom_fingers(fingers.get(i).map_or("", String::as_str), row.len())?
+                }
+                None => standard_fingers(i, row.len(), definition.iso)?,
+            };
+            let key = |c: char| Some(c).filter(|c| *c != ' ');
+            rows.push(
+                row.iter()
+                    .zip(&shifted)
+                    .zip(&altgr)
+                    .zip(fingers)
+                    .map(|(((&unshifted, &shifted), &altgr), finger)| Key {
+                        unshifted: key(unshifted),
+                        shifted: key(shifted),
+                        altgr: key(altgr),
+                        finger,
+                    })
+                    .collect(),
+            );
+        }
+        Ok(Layout {
+            name: definition.name,
+            iso: definition.iso,
+            rows,
+        })
+    }
+
+    /// Where `c` is typed, if the layout has it, preferring keys without
+    /// shift, then without AltGr. Spaces are typed on the space bar and
+    /// line breaks on the enter key, at the end of the home row.
+    pub fn locate(&self, c: char) -> Option<KeyPosition> {
+        match c {
+            ' ' => {
+                return Some(KeyPosition {
+                    row: SPACE_ROW,
+                    column: 0,
+                    shift: false,
+                    altgr: false,
+                    finger: Finger::Thumb,
+                })
+            }
+            '\n' => {
+                return Some(KeyPosition {
+                    row: HOME_ROW,
+                    column: self.rows[HOME_ROW].len(),
+                    shift: false,
+                    altgr: false,
+                    finger: Finger::RightPinky,
+                })
+            }
+            _ => {}
+        }
+        for (shift, altgr) in [(false, false), (true, false), (false, true)] {
+            for (row, keys) in self.rows.iter().enumerate() {
+                let found = keys
+                    .iter()
+                    .position(|key| key.level(shift, altgr) == Some(c));
+                if let Some(column) = found {
+                    return Some(KeyPosition {
+                        row,
+                        column,
+                        shift,
+                        altgr,
+                        finger: keys[column].finger,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// What the key that types `c` in this layout types in `target`, so
+    /// that typing on a keyboard set to this layout emulates `target`.
+    /// Characters either layout lacks, and spaces and line breaks, are
+    /// left as they are.
+    pub fn translate(&self, c: char, target: &Layout) -> char {
+        let position = match self.locate(c) {
+            Some(p) if p.row < SPACE_ROW && p.column < self.rows[p.row].len() => p,
+            _ => return c,
+        };
+        // the ISO key shifts the rest of the bottom row along by one
+        let iso = |layout: &Layout| (position.row == 3 && layout.iso) as usize;
+        let key = (position.column + iso(target))
+            .checked_sub(iso(self))
+            .and_then(|column| target.rows[position.row].get(column));
+        let translated = key.and_then(|key| key.level(position.shift, position.altgr));
+        translated.unwrap_or(c)
+    }
+}
+
+impl std::str::FromStr for Layout {
+    type Err = String;
+
+    /// A built-in layout by name, or else a layout file.
+    fn from_str(s: &str) -> Result<Layout, String> {
+        match Layout::built_in(s) {
+            Some(layout) => Ok(layout),
+            None => Layout::load(s).map_err(|e| format!("Not a layout name or file: {}", e)),
+        }
+    }
+}
+
+fn standard_fingers(row: usize, keys: usize, iso: bool) -> Result<Vec<Finger>, Box<dyn Error>> {
+    let mut fingers = FINGERS[row].to_vec();
+    if iso && row == 3 {
+        fingers.insert(0, Finger::LeftPinky);
+    }
+    if keys > fingers.len() {
+        return Err(format!("Row {} has more keys than a keyboard", row + 1).into());
+    }
+    fingers.truncate(keys);
+    Ok(fingers)
+}
+
+fn custom_fingers(digits: &str, keys: usize) -> Result<Vec<Finger>, Box<dyn Error>> {
+    let fingers = digits
+        .chars()
+        .map(|d| match d.to_digit(10) {
+            Some(d) if (d as usize) < Finger::ALL.len() => Ok(Finger::ALL[d as usize]),
+            _ => Err(format!("'{}' is not a finger, from 0 to 8", d)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if fingers.len() != keys {
+        return Err("Each row needs a finger for every key".into());
+    }
+    Ok(fingers)
+}
@@ -0,0 +1,7 @@
+#[cfg(test)]
+mod tests;
+
+#[allow(clippy::module_inception)]
+mod layout;
+
+pub use layout::{Finger, Hand, KeyPosition, Layout, HOME_ROW, ROWS, SPACE_ROW};
@@ -0,0 +1,121 @@
+use super::*;
+
+#[test]
+fn built_in_layouts_load() {
+    for name in ["qwerty", "dvorak", "colemak", "workman", "azerty", "qwertz"] {
+        let layout = Layout::built_in(name).unwrap();
+        assert_eq!(layout.rows.len(), 4);
+    }
+    assert_eq!(Layout::built_in("Dvorak").unwrap().name, "Dvorak");
+    assert!(Layout::built_in("nope").is_none());
+}
+
+#[test]
+fn characters_are_located_on_their_keys() {
+    let qwerty = Layout::built_in("qwerty").unwrap();
+    let f = qwerty.locate('f').unwrap();
+    assert_eq!((f.row, f.column, f.shift), (2, 3, false));
+    assert_eq!(f.finger, Finger::LeftIndex);
+    let colon = qwerty.locate(':').unwrap();
+    assert!(colon.shift);
+    assert_eq!(colon.finger, Finger::RightPinky);
+    assert_eq!(colon.shift_finger(), Some(Finger::LeftPinky));
+    assert_eq!(qwerty.locate(' ').unwrap().finger, Finger::Thumb);
+    assert_eq!(qwerty.locate('\n').unwrap().row, HOME_ROW);
+    assert!(qwerty.locate('é').is_none());
+}
+
+#[test]
+fn every_built_in_layout_types_all_of_ascii() {
+    for name in ["qwerty", "dvorak", "colemak", "workman", "azerty", "qwertz"] {
+        let layout = Layout::built_in(name).unwrap();
+        for c in (' '..='~').chain(['\n']) {
+            assert!(layout.locate(c).is_some(), "{} has no '{}'", name, c);
+        }
+    }
+}
+
+#[test]
+fn altgr_characters_are_located_after_shifted_ones() {
+    let azerty = Layout::built_in("azerty").unwrap();
+    let at = azerty.locate('@').unwrap();
+    assert_eq!((at.row, at.column, at.shift, at.altgr), (0, 10, false, true));
+    assert_eq!(at.shift_finger(), None);
+    let qwertz = Layout::built_in("qwertz").unwrap();
+    assert!(qwertz.locate('|').unwrap().altgr);
+    assert!(!qwertz.locate('#').unwrap().altgr);
+}
+
+#[test]
+fn altgr_keys_translate_between_layouts() {
+    let azerty = Layout::built_in("azerty").unwrap();
+    let qwertz = Layout::built_in("qwertz").unwrap();
+    // AltGr with the key right of 9
+    assert_eq!(azerty.translate('@', &qwertz), '}');
+}
+
+#[test]
+fn layouts_move_characters_between_fingers() {
+    let dvorak = Layout::built_in("dvorak").unwrap();
+    assert_eq!(dvorak.locate('u').unwrap().finger, Finger::LeftIndex);
+    assert_eq!(dvorak.locate('s').unwrap().finger, Finger::RightPinky);
+}
+
+#[test]
+fn iso_layouts_have_a_key_left_of_the_bottom_row() {
+    let qwertz = Layout::built_in("qwertz").unwrap();
+    assert!(qwertz.iso);
+    let y = qwertz.locate('y').unwrap();
+    assert_eq!((y.row, y.column), (3, 1));
+    assert_eq!(y.finger, Finger::LeftPinky);
+    assert_eq!(qwertz.locate('<').unwrap().finger, Finger::LeftPinky);
+}
+
+#[test]
+fn custom_layouts_may_set_their_own_fingers() {
+    let json = r#"{
+        "name": "Tiny",
+        "rows": ["12", "ab", "cd", "ef"],
+        "shifted": ["!@", "AB", "CD", "EF"],
+        "fingers": ["00", "11", "37", "58"]
+    }"#;
+    let layout = Layout::parse(json).unwrap();
+    assert_eq!(layout.locate('D').unwrap().finger, Finger::RightRing);
+    assert_eq!(layout.locate('f').unwrap().finger, Finger::RightPinky);
+}
+
+#[test]
+fn malformed_layouts_are_rejected() {
+    let rows = r#"{"name": "x", "rows": ["1", "a"], "shifted": ["!", "A"]}"#;
+    assert!(Layout::parse(rows).is_err());
+    let lengths =
+        r#"{"name": "x", "rows": ["1", "a", "b", "c"], "shifted": ["!", "AB", "B", "C"]}"#;
+    assert!(Layout::parse(lengths).is_err());
+    let fingers = r#"{"name": "x", "rows": ["1", "a", "b", "c"],
+        "shifted": ["!", "A", "B", "C"], "fingers": ["9", "0", "0", "0"]}"#;
+    assert!(Layout::parse(fingers).is_err());
+    let altgr = r#"{"name": "x", "rows": ["1", "a", "b", "c"],
+        "shifted": ["!", "A", "B", "C"], "altgr": ["@", "  ", " ", " "]}"#;
+    assert!(Layout::parse(altgr).is_err());
+    assert!("no/such/layout.json".parse::<Layout>().is_err());
+}
+
+#[test]
+fn keys_translate_between_layouts() {
+    let qwerty = Layout::built_in("qwerty").unwrap();
+    let colemak = Layout::built_in("colemak").unwrap();
+    let typed = "Hello; jk".chars().map(|c| qwerty.translate(c, &colemak));
+    assert_eq!(typed.collect::<String>(), "Hfiiyo ne");
+    assert_eq!(qwerty.translate(':', &colemak), 'O');
+    assert_eq!(qwerty.translate('\u{e9}', &colemak), '\u{e9}');
+}
+
+#[test]
+fn translation_lines_up_iso_and_ansi_bottom_rows() {
+    let qwerty = Layout::built_in("qwerty").unwrap();
+    let qwertz = Layout::built_in("qwertz").unwrap();
+    assert_eq!(qwertz.translate('y', &qwerty), 'z');
+    assert_eq!(qwerty.translate('z', &qwertz), 'y');
+    // the ISO key has no counterpart on ANSI keyboards
+    assert_eq!(qwertz.translate('<', &qwerty), '<');
+}
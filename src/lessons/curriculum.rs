@@ -0,0 +1,86 @@
+use crate::text_model::{text_id, unsupported_character};
+use serde::{Deserialize, Serialize};
+use std::{boxed::Box, error::Error, path::Path};
+
+/// The curriculum klata ships with, from the home row out to symbols.
+const BUILT_IN: &str = include_str!("curriculum.json");
+
+/// Lessons in the order they are unlocked, each adding keys to those of
+/// the lessons before it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Curriculum {
+    pub lessons: Vec<Lesson>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Lesson {
+    pub name: String,
+    /// The keys the lesson introduces.
+    pub keys: String,
+    /// Accuracy, between 0 and 1, needed to unlock the next lesson.
+    pub min_accuracy: f64,
+    /// Speed, in words per minute, needed to unlock the next lesson.
+    pub min_wpm: f64,
+    /// How many words each drill has.
+    #[serde(default = "default_words")]
+    pub words: usize,
+}
+
+fn default_words() -> usize {
+    20
+}
+
+impl Default for Curriculum {
+    fn default() -> Curriculum {
+        Curriculum::parse(BUILT_IN).expect("the built-in curriculum is valid")
+    }
+}
+
+impl Curriculum {
+    pub fn parse(s: &str) -> Result<Curriculum, Box<dyn Error>> {
+        let curriculum: Curriculum = serde_json::from_str(s)?;
+        if curriculum.lessons.is_empty() {
+            return Err("The curriculum has no lessons".into());
+        }
+        for lesson in &curriculum.lessons {
+            if lesson.keys.trim().is_empty() {
+                return Err(format!("Lesson '{}' introduces no keys", lesson.name).into());
+            }
+            if let Some(c) = unsupported_character(&lesson.keys) {
+                return Err(format!(
+                    "Lesson '{}' has a key klata cannot type: '{}'",
+                    lesson.name, c
+                )
+                .into());
+            }
+        }
+        Ok(curriculum)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Curriculum, Box<dyn Error>> {
+        Curriculum::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// A stable identifier for the curriculum, hashed from the names and
+    /// keys of its lessons, so that progress through each is kept apart.
+    pub fn id(&self) -> String {
+        let lessons = self
+            .lessons
+            .iter()
+            .map(|lesson| format!("{}\n{}\n", lesson.name, lesson.keys))
+            .collect::<String>();
+        text_id(&lessons)
+    }
+
+    /// Every key that may be typed in the `index`th lesson: its own and
+    /// those of the lessons before it.
+    pub fn allowed(&self, index: usize) -> Vec<char> {
+        let mut allowed = self.lessons[..=index]
+            .iter()
+            .flat_map(|lesson| lesson.keys.chars())
+            .collect::<Vec<_>>();
+        allowed.sort_unstable();
+        allowed.dedup();
+        allowed
+    }
+}
@@ -0,0 +1,52 @@
+use super::Curriculum;
+use rand::{seq::SliceRandom, Rng};
+
+/// Common words, drilled whenever a lesson has the keys to type them.
+pub const WORDS: &str = include_str!("words.txt");
+
+/// How often a drill uses a real word rather than a made-up group of
+/// keys, when there are words to use.
+const WORD_CHANCE: f64 = 0.6;
+
+/// A drill for the `index`th lesson of `curriculum`, using only the keys
+/// it allows and favouring the ones it introduces.
+pub fn drill<R: Rng>(curriculum: &Curriculum, index: usize, rng: &mut R) -> String {
+    let lesson = &curriculum.lessons[index];
+    let allowed = curriculum.allowed(index);
+    let new = lesson.keys.chars().collect::<Vec<_>>();
+    let words = WORDS
+        .lines()
+        .filter(|word| word.chars().all(|c| allowed.contains(&c)))
+        .collect::<Vec<_>>();
+    let with_new = words
+        .iter()
+        .copied()
+        .filter(|word| word.chars().any(|c| new.contains(&c)))
+        .collect::<Vec<_>>();
+    (0..lesson.words)
+        .map(|_| {
+            if !words.is_empty() && rng.gen_bool(WORD_CHANCE) {
+                // words with the new keys in, when there are any
+                let pool = if with_new.is_empty() {
+                    &words
+                } else {
+                    &with_new
+                };
+                pool.choose(rng).unwrap().to_string()
+            } else {
+                group(&allowed, &new, rng)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A made-up word of two to five keys, half of them new.
+fn group<R: Rng>(allowed: &[char], new: &[char], rng: &mut R) -> String {
+    (0..rng.gen_range(2..=5))
+        .map(|_| {
+            let keys = if rng.gen_bool(0.5) { new } else { allowed };
+            *keys.choose(rng).unwrap()
+        })
+        .collect()
+}
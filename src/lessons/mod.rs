@@ -0,0 +1,10 @@
+#[cfg(test)]
+mod tests;
+
+mod curriculum;
+mod drill;
+mod progress;
+
+pub use curriculum::Curriculum;
+pub use drill::{drill, WORDS};
+pub use progress::Progress;
@@ -0,0 +1,92 @@
+use super::Curriculum;
+use crate::history::Record;
+use serde::{Deserialize, Serialize};
+use std::{
+    boxed::Box,
+    collections::HashMap,
+    error::Error,
+    path::{Path, PathBuf},
+};
+
+/// How far a profile has got through a curriculum.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Progress {
+    /// How many lessons may be taken, counting from the first.
+    pub unlocked: usize,
+    /// The best completed run of each lesson, by name.
+    pub best: HashMap<String, Best>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Best {
+    pub wpm: f64,
+    pub accuracy: f64,
+}
+
+impl Default for Progress {
+    fn default() -> Progress {
+        Progress {
+            unlocked: 1,
+            best: HashMap::new(),
+        }
+    }
+}
+
+impl Progress {
+    /// The file for the progress of `profile` through `curriculum` in the
+    /// user's data directory, if there is one.
+    pub fn user_path(profile: &str, curriculum: &Curriculum) -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| {
+            dir.join("klata")
+                .join("profiles")
+                .join(profile)
+                .join("progress")
+                .join(format!("{}.json", curriculum.id()))
+        })
+    }
+
+    /// The progress saved at `path`, or none at all if nothing is saved.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Progress, Box<dyn Error>> {
+        match std::fs::read_to_string(path) {
+            Ok(json) => Ok(serde_json::from_str(&json)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Progress::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>> {
+        if let Some(dir) = path.as_ref().parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn is_unlocked(&self, index: usize) -> bool {
+        index < self.unlocked
+    }
+
+    /// Keeps the result of a run through the `index`th lesson, unlocking
+    /// the next if it was fast and accurate enough. Returns whether it was.
+    pub fn record(&mut self, curriculum: &Curriculum, index: usize, record: &Record) -> bool {
+        let lesson = &curriculum.lessons[index];
+        if !record.completed {
+            return false;
+        }
+        let best = self.best.entry(lesson.name.clone()).or_insert(Best {
+            wpm: 0.0,
+            accuracy: 0.0,
+        });
+        if record.wpm > best.wpm {
+            *best = Best {
+                wpm: record.wpm,
+                accuracy: record.accuracy,
+            };
+        }
+        let passed = record.accuracy >= lesson.min_accuracy && record.wpm >= lesson.min_wpm;
+        if passed {
+            self.unlocked = self.unlocked.max(index + 2).min(curriculum.lessons.len());
+        }
+        passed
+    }
+}
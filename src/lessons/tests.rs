@@ -0,0 +1,125 @@
+use super::*;
+use crate::history::Record;
+use crate::text_model::Correction;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+fn record(completed: bool, wpm: f64, accuracy: f64) -> Record {
+    Record {
+        timestamp: 0,
+        author: None,
+        completed,
+        failure: None,
+        correction: Correction::Free,
+        wpm,
+        accuracy,
+        errors: 0,
+        elapsed_secs: 10.0,
+        idle_secs: 0.0,
+        analysis: None,
+        difficulty: None,
+    }
+}
+
+#[test]
+fn built_in_curriculum_goes_from_home_row_to_symbols() {
+    let names = Curriculum::default()
+        .lessons
+        .iter()
+        .map(|lesson| lesson.name.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        ["Home row", "Top row", "Bottom row", "Numbers", "Symbols"]
+    );
+}
+
+#[test]
+fn allowed_keys_build_on_earlier_lessons() {
+    let curriculum = Curriculum::default();
+    assert_eq!(curriculum.allowed(0).len(), 10);
+    let allowed = curriculum.allowed(1);
+    assert!(allowed.contains(&'a') && allowed.contains(&'q'));
+    assert!(!allowed.contains(&'z'));
+}
+
+#[test]
+fn curricula_must_be_typeable() {
+    assert!(Curriculum::parse(r#"{"lessons": []}"#).is_err());
+    let no_keys =
+        r#"{"lessons": [{"name": "A", "keys": " ", "min_accuracy": 0.9, "min_wpm": 10}]}"#;
+    assert!(Curriculum::parse(no_keys).is_err());
+    let accents =
+        r#"{"lessons": [{"name": "A", "keys": "é", "min_accuracy": 0.9, "min_wpm": 10}]}"#;
+    assert!(Curriculum::parse(accents).is_err());
+    let fine = r#"{"lessons": [{"name": "A", "keys": "ab", "min_accuracy": 0.9, "min_wpm": 10}]}"#;
+    assert_eq!(Curriculum::parse(fine).unwrap().lessons[0].words, 20);
+}
+
+#[test]
+fn drills_use_only_allowed_keys_and_favour_new_ones() {
+    let curriculum = Curriculum::default();
+    for index in 0..curriculum.lessons.len() {
+        let text = drill(&curriculum, index, &mut ChaCha8Rng::seed_from_u64(1));
+        let allowed = curriculum.allowed(index);
+        assert!(
+            text.chars().all(|c| c == ' ' || allowed.contains(&c)),
+            "{}",
+            text
+        );
+        let new = &curriculum.lessons[index].keys;
+        assert!(text.chars().any(|c| new.contains(c)), "{}", text);
+        assert_eq!(text.split(' ').count(), 20);
+    }
+}
+
+#[test]
+fn drills_are_reproducible_from_a_seed() {
+    let curriculum = Curriculum::default();
+    let a = drill(&curriculum, 2, &mut ChaCha8Rng::seed_from_u64(7));
+    let b = drill(&curriculum, 2, &mut ChaCha8Rng::seed_from_u64(7));
+    let c = drill(&curriculum, 2, &mut ChaCha8Rng::seed_from_u64(8));
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
+
+#[test]
+fn lessons_unlock_when_fast_and_accurate_enough() {
+    let curriculum = Curriculum::default();
+    let mut progress = Progress::default();
+    assert!(progress.is_unlocked(0) && !progress.is_unlocked(1));
+    assert!(!progress.record(&curriculum, 0, &record(true, 40.0, 0.9)));
+    assert!(!progress.record(&curriculum, 0, &record(true, 10.0, 1.0)));
+    assert!(!progress.record(&curriculum, 0, &record(false, 90.0, 1.0)));
+    assert!(!progress.is_unlocked(1));
+    assert!(progress.record(&curriculum, 0, &record(true, 30.0, 0.97)));
+    assert!(progress.is_unlocked(1) && !progress.is_unlocked(2));
+    let best = progress.best["Home row"];
+    assert_eq!((best.wpm, best.accuracy), (40.0, 0.9));
+}
+
+#[test]
+fn progress_is_saved_per_profile() {
+    let dir = std::env::temp_dir().join(format!("klata-progress-{}", std::process::id()));
+    let path = dir.join("someone").join("progress.json");
+    assert_eq!(Progress::load(&path).unwrap(), Progress::default());
+    let mut progress = Progress::default();
+    progress.record(&Curriculum::default(), 0, &record(true, 30.0, 1.0));
+    progress.save(&path).unwrap();
+    let loaded = Progress::load(&path).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(loaded, progress);
+    assert!(Progress::user_path("someone", &Curriculum::default())
+        .is_none_or(|path| path.parent().unwrap().ends_with("someone/progress")));
+}
+
+#[test]
+fn progress_is_kept_apart_per_curriculum() {
+    let built_in = Curriculum::default();
+    let mut other = built_in.clone();
+    other.lessons.truncate(2);
+    let mut harder = built_in.clone();
+    harder.lessons[0].min_wpm += 10.0;
+    assert_ne!(built_in.id(), other.id());
+    assert_eq!(built_in.id(), harder.id());
+}
@@ -3,11 +3,141 @@ use std::io;
 
 mod app;
 mod cli;
+mod difficulty;
+mod generator;
+mod history;
+mod layout;
+mod lessons;
+mod race;
+mod session;
 mod text_model;
+mod typ;
 mod widgets;
 
-fn main() -> Result<(), app::AppError> {
-    let cli_args = cli::Cli::parse();
-    let app = app::App::from_file(cli_args.file)?;
-    app.run(io::stdout())
+fn main() {
+    if let Err(e) = run(cli::Cli::parse()) {
+        eprintln!("klata: {}", e);
+        std::process::exit(e.exit_code());
+    }
+}
+
+fn run(cli_args: cli::Cli) -> Result<(), app::AppError> {
+    match cli_args.command {
+        Some(cli::Command::Replay { log, speed }) => {
+            app::App::replay(log, speed)?.run(io::stdout())
+        }
+        Some(cli::Command::Validate { paths, json }) => {
+            let problems = text_model::validate(&paths)?;
+            if json {
+                let problems = problems.iter().map(text_model::Problem::from).collect::<Vec<_>>();
+                println!("{}", serde_json::to_string(&problems)?);
+            } else {
+                problems.iter().for_each(|problem| println!("{}\n", problem));
+            }
+            match problems.len() {
+                0 => Ok(()),
+                count => Err(app::AppError::InvalidTexts(count)),
+            }
+        }
+        Some(cli::Command::Import { inputs, input, output }) => {
+            let mut texts = Vec::new();
+            let mut problems = Vec::new();
+            for path in &inputs {
+                let imported = app::import_texts(path, &input.options())?;
+                texts.extend(imported.texts);
+                problems.extend(imported.problems);
+            }
+            let mut seen = std::collections::HashSet::new();
+            texts.retain(|data: &text_model::Data| seen.insert(data.text.clone()));
+            problems.iter().for_each(|problem| eprintln!("{}\n", problem));
+            eprintln!("Imported {} texts, skipped {}", texts.len(), problems.len());
+            if texts.is_empty() && !problems.is_empty() {
+                return Err(app::AppError::InvalidTexts(problems.len()));
+            }
+            let library = text_model::library_to_xml(&texts);
+            match output.as_str() {
+                "-" => print!("{}", library),
+                path => std::fs::write(path, library)?,
+            }
+            Ok(())
+        }
+        Some(cli::Command::Script { session, keys }) => {
+            let script = match keys.as_str() {
+                "-" => io::read_to_string(io::stdin())?,
+                path => app::read_file(path)?,
+            };
+            let path = session.file.as_ref().unwrap();
+            let settings = session.settings();
+            let text = app::read_text(path, &session.options(), settings.seed)?;
+            let result = app::run_script(&text, settings, &app::Script::parse(&script)?)
+                .map_err(app::AppError::from)?;
+            println!("{}", serde_json::to_string(&result)?);
+            Ok(())
+        }
+        Some(cli::Command::Tutorial { file, label, correction, layout, os_layout }) => {
+            let difficulty_index = difficulty::Index::user_path();
+            let settings = session::Settings { correction, layout, os_layout, difficulty_index, ..Default::default() };
+            app::App::tutorial(file, label.as_deref(), settings)?.run(io::stdout())
+        }
+        Some(cli::Command::Lessons { profile, curriculum, seed, correction, layout, os_layout }) => {
+            let seed = cli::seed_or_random(seed);
+            let settings = session::Settings { correction, seed, layout, os_layout, ..Default::default() };
+            app::App::lessons(curriculum.as_deref(), &profile, settings)?.run(io::stdout())
+        }
+        Some(cli::Command::Generate { corpus, input, level, order, keys, seed, print, correction, layout, os_layout }) => {
+            let mut texts = Vec::new();
+            for path in &corpus {
+                texts.extend(app::import_texts(path, &input.options())?.texts);
+            }
+            let mut model = generator::Model::train(texts.iter().map(|data| data.text.as_str()), level, order);
+            if let Some(keys) = &keys {
+                model.restrict(keys);
+            }
+            if model.is_empty() {
+                return Err("Nothing in the corpus to generate text from".into());
+            }
+            let mut generator = generator::Generator::new(model, cli::seed_or_random(seed));
+            match print {
+                Some(chars) => {
+                    println!("{}", generator.generate(chars));
+                    Ok(())
+                }
+                None => {
+                    let settings = session::Settings { correction, layout, os_layout, ..Default::default() };
+                    app::App::generate(generator, settings)?.run(io::stdout())
+                }
+            }
+        }
+        Some(cli::Command::Stats) => {
+            let history = history::History::user().ok_or("No data directory for the history")?;
+            let records = history.records()?;
+            if records.skipped > 0 {
+                eprintln!("Skipped {} unreadable lines of the history", records.skipped);
+            }
+            println!("{}", history::report(&records.records));
+            Ok(())
+        }
+        Some(cli::Command::Serve { session, port, name }) => {
+            let mut app = app::App::serve(session.file.as_ref().unwrap(), &session.options(), port, &name, session.settings())?;
+            if let Some(path) = &session.typing.record {
+                app = app.record_keystrokes(path);
+            }
+            app.run(io::stdout())
+        }
+        Some(cli::Command::Join { address, name, typing }) => {
+            let mut app = app::App::join(&address, &name, typing.settings())?;
+            if let Some(path) = &typing.record {
+                app = app.record_keystrokes(path);
+            }
+            app.run(io::stdout())
+        }
+        None => {
+            let session = cli_args.session;
+            let mut app = app::App::from_file(session.file.as_ref().unwrap(), &session.options(), session.settings())?;
+            if let Some(path) = &session.typing.record {
+                app = app.record_keystrokes(path);
+            }
+            app.run(io::stdout())
+        }
+    }
 }
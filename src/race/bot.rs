@@ -0,0 +1,176 @@
+use crate::session::{Ghost, Key, KeystrokeLog};
+use crate::text_model::Correction;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::{boxed::Box, error::Error, time::Duration};
+
+/// How a simulated typist behaves.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BotProfile {
+    pub name: String,
+    pub wpm: f64,
+    /// Probability of typing each character right, between 0 and 1.
+    pub accuracy: f64,
+    /// How unevenly keystrokes are spaced, between 0 (metronomic) and 1.
+    pub burstiness: f64,
+    /// Probability of noticing and fixing each mistake, between 0 and 1.
+    pub correction: f64,
+}
+
+impl BotProfile {
+    pub const PRESETS: [&'static str; 4] = ["novice", "average", "fast", "pro"];
+
+    fn preset(name: &str) -> Option<BotProfile> {
+        let (wpm, accuracy, burstiness, correction) = match name {
+            "novice" => (25.0, 0.9, 0.6, 0.9),
+            "average" => (45.0, 0.95, 0.4, 0.8),
+            "fast" => (75.0, 0.97, 0.3, 0.7),
+            "pro" => (110.0, 0.99, 0.2, 0.95),
+            _ => return None,
+        };
+        Some(BotProfile {
+            name: name.to_string(),
+            wpm,
+            accuracy,
+            burstiness,
+            correction,
+        })
+    }
+}
+
+impl std::str::FromStr for BotProfile {
+    type Err = String;
+
+    /// Parses either a preset name or `NAME:WPM[:ACCURACY[:BURSTINESS[:CORRECTION]]]`.
+    fn from_str(s: &str) -> Result<BotProfile, String> {
+        if let Some(profile) = BotProfile::preset(s) {
+            return Ok(profile);
+        }
+        let mut fields = s.split(':');
+        let name = fields.next().unwrap_or_default().to_string();
+        let mut numbers = Vec::new();
+        for field in fields {
+            numbers.push(
+                field
+                    .parse::<f64>()
+                    .map_err(|_| format!("Invalid number '{}' in bot '{}'", field, s))?,
+            );
+        }
+        if name.is_empty() || numbers.is_empty() || numbers.len() > 4 {
+            return Err(format!(
+                "Expected one of {} or NAME:WPM[:ACCURACY[:BURSTINESS[:CORRECTION]]], got '{}'",
+                BotProfile::PRESETS.join(", "),
+                s
+            ));
+        }
+        // slower than this and the simulated times overflow a Duration
+        if !numbers[0].is_finite() || numbers[0] < 1.0 {
+            return Err(format!(
+                "Expected a WPM of at least 1 in bot '{}', got '{}'",
+                s, numbers[0]
+            ));
+        }
+        if let Some(p) = numbers[1..].iter().find(|p| !(0.0..=1.0).contains(*p)) {
+            return Err(format!(
+                "Expected accuracy, burstiness and correction between 0 and 1 in bot '{}', got '{}'",
+                s, p
+            ));
+        }
+        let average = BotProfile::preset("average").unwrap();
+        Ok(BotProfile {
+            name,
+            wpm: numbers[0],
+            accuracy: numbers.get(1).copied().unwrap_or(average.accuracy),
+            burstiness: numbers.get(2).copied().unwrap_or(average.burstiness),
+            correction: numbers.get(3).copied().unwrap_or(average.correction),
+        })
+    }
+}
+
+/// A simulated opponent. Its whole run is simulated up front from a seed,
+/// so a race against the same bots and seed is reproducible.
+pub struct Bot {
+    profile: BotProfile,
+    ghost: Ghost,
+    finish_time: Option<Duration>,
+}
+
+impl Bot {
+    pub fn new(profile: BotProfile, source: &str, seed: u64) -> Result<Bot, Box<dyn Error>> {
+        let log = simulate(&profile, source, seed)?;
+        Ok(Bot {
+            ghost: Ghost::new(&log),
+            finish_time: log.finish_time()?,
+            profile,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.profile.name
+    }
+
+    pub fn position(&self, elapsed: Duration) -> usize {
+        self.ghost.position(elapsed)
+    }
+
+    pub fn finish_time(&self) -> Option<Duration> {
+        self.finish_time
+    }
+}
+
+fn simulate(profile: &BotProfile, source: &str, seed: u64) -> Result<KeystrokeLog, Box<dyn Error>> {
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut log = KeystrokeLog::new(source, Correction::Free);
+    let mut text = log.text_model()?;
+    let targets = text.characters().map(|c| c.value()).collect::<Vec<_>>();
+    if profile.wpm <= 0.0 {
+        return Ok(log);
+    }
+    let interval = 60.0 / (profile.wpm * 5.0);
+    let burstiness = profile.burstiness.clamp(0.0, 1.0);
+    let mut elapsed = 0.0;
+    let next_interval =
+        |rng: &mut ChaCha8Rng| interval * (1.0 + burstiness * (rng.gen::<f64>() * 2.0 - 1.0));
+    while let Some(&target) = targets.get(text.cursor()) {
+        elapsed += next_interval(&mut rng);
+        if target == ' ' && rng.gen::<f64>() < burstiness / 4.0 {
+            // hesitate between words
+            elapsed += interval * 4.0;
+        }
+        if rng.gen::<f64>() < profile.accuracy {
+            log.press(
+                Key::Char(target),
+                &mut text,
+                Duration::from_secs_f64(elapsed),
+            );
+            continue;
+        }
+        log.press(
+            Key::Char(typo(target)),
+            &mut text,
+            Duration::from_secs_f64(elapsed),
+        );
+        if rng.gen::<f64>() < profile.correction {
+            // notice the mistake, then fix it
+            elapsed += interval * 2.0 + next_interval(&mut rng);
+            log.press(Key::Backspace, &mut text, Duration::from_secs_f64(elapsed));
+            elapsed += next_interval(&mut rng);
+            log.press(
+                Key::Char(target),
+                &mut text,
+                Duration::from_secs_f64(elapsed),
+            );
+        }
+    }
+    Ok(log)
+}
+
+fn typo(c: char) -> char {
+    match c {
+        'z' => 'a',
+        'Z' => 'A',
+        ' ' => 'n',
+        c if c.is_ascii_alphabetic() => (c as u8 + 1) as char,
+        _ => ' ',
+    }
+}
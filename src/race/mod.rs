@@ -0,0 +1,10 @@
+#[cfg(test)]
+mod tests;
+
+mod bot;
+mod net;
+mod standings;
+
+pub use bot::{Bot, BotProfile};
+pub use net::{Client, Host, LaneInfo, Link};
+pub use standings::Standing;
@@ -0,0 +1,119 @@
+use super::protocol::{receive, send, Message, PROTOCOL_VERSION};
+use super::{lock, LaneInfo, Link};
+use std::{
+    boxed::Box,
+    error::Error,
+    io::BufReader,
+    net::{TcpStream, ToSocketAddrs},
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+/// How long to wait for the host to answer a hello.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Default)]
+struct Shared {
+    lanes: Vec<LaneInfo>,
+    countdown: Option<u64>,
+    disconnected: bool,
+}
+
+/// Joins a race hosted elsewhere.
+pub struct Client {
+    stream: TcpStream,
+    player: usize,
+    source: String,
+    shared: Arc<Mutex<Shared>>,
+    last_sent: Option<LaneInfo>,
+}
+
+impl Client {
+    pub fn connect<A: ToSocketAddrs>(address: A, name: &str) -> Result<Client, Box<dyn Error>> {
+        let mut stream = TcpStream::connect(address)?;
+        let hello = Message::Hello {
+            version: PROTOCOL_VERSION,
+            name: name.to_string(),
+        };
+        send(&mut stream, &hello)?;
+        stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let (player, source) = match receive(&mut reader)? {
+            Some(Message::Welcome {
+                version,
+                player,
+                source,
+            }) if version == PROTOCOL_VERSION => (player, source),
+            Some(Message::Rejected { reason }) => return Err(reason.into()),
+            _ => return Err("The host did not welcome us into the race".into()),
+        };
+        stream.set_read_timeout(None)?;
+
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let listening = shared.clone();
+        thread::spawn(move || {
+            while let Ok(Some(message)) = receive(&mut reader) {
+                let mut shared = lock(&listening);
+                match message {
+                    Message::Start { countdown } => shared.countdown = Some(countdown),
+                    Message::Lanes { lanes } => shared.lanes = lanes,
+                    _ => {}
+                }
+            }
+            // nobody else's progress will be heard now
+            let mut shared = lock(&listening);
+            shared.disconnected = true;
+            shared.lanes.iter_mut().for_each(|lane| lane.done = true);
+        });
+        Ok(Client {
+            stream,
+            player,
+            source,
+            shared,
+            last_sent: None,
+        })
+    }
+
+    /// The text file content the host chose for the race.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Link for Client {
+    fn is_host(&self) -> bool {
+        false
+    }
+
+    fn start(&mut self, _countdown: u64) {}
+
+    fn started(&mut self) -> Option<u64> {
+        lock(&self.shared).countdown.take()
+    }
+
+    fn update(&mut self, lane: LaneInfo) {
+        if self.last_sent.as_ref() == Some(&lane) {
+            return;
+        }
+        let message = Message::Progress { lane: lane.clone() };
+        if send(&mut self.stream, &message).is_ok() {
+            self.last_sent = Some(lane);
+        }
+    }
+
+    fn others(&self) -> Vec<LaneInfo> {
+        let shared = lock(&self.shared);
+        shared
+            .lanes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != self.player)
+            .map(|(_, lane)| lane.clone())
+            .collect()
+    }
+
+    fn disconnected(&self) -> bool {
+        lock(&self.shared).disconnected
+    }
+}
@@ -0,0 +1,186 @@
+use super::protocol::{receive, send, Message, PROTOCOL_VERSION};
+use super::{lock, LaneInfo, Link};
+use std::{
+    io::{self, BufReader},
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+/// How long a client may take to accept a message before it is dropped
+/// from the race.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+struct Player {
+    lane: LaneInfo,
+    /// Messages for the client's writer thread: `None` for the host itself,
+    /// or once a client has gone.
+    outbox: Option<Sender<Message>>,
+}
+
+struct Shared {
+    source: String,
+    started: bool,
+    players: Vec<Player>,
+}
+
+impl Shared {
+    /// Queues `message` for every client. Nothing is written here, so a slow
+    /// client can't hold up the host or the other clients.
+    fn broadcast(&self, message: &Message) {
+        for outbox in self.players.iter().filter_map(|p| p.outbox.as_ref()) {
+            let _ = outbox.send(message.clone());
+        }
+    }
+}
+
+/// Hosts a race, relaying every racer's progress to every client. The
+/// host is also a racer, always player 0.
+pub struct Host {
+    shared: Arc<Mutex<Shared>>,
+    address: SocketAddr,
+    last_sent: Option<Vec<LaneInfo>>,
+}
+
+impl Host {
+    pub fn bind<A: ToSocketAddrs>(address: A, name: &str, source: &str) -> io::Result<Host> {
+        let listener = TcpListener::bind(address)?;
+        let address = listener.local_addr()?;
+        let shared = Arc::new(Mutex::new(Shared {
+            source: source.to_string(),
+            started: false,
+            players: vec![Player {
+                lane: LaneInfo {
+                    name: name.to_string(),
+                    ..LaneInfo::default()
+                },
+                outbox: None,
+            }],
+        }));
+        let accepting = shared.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let shared = accepting.clone();
+                thread::spawn(move || serve(stream, shared));
+            }
+        });
+        Ok(Host {
+            shared,
+            address,
+            last_sent: None,
+        })
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+}
+
+/// Welcomes a client and then records its progress until it hangs up.
+fn serve(stream: TcpStream, shared: Arc<Mutex<Shared>>) -> io::Result<()> {
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    let name = match receive(&mut reader)? {
+        Some(Message::Hello { version, name }) if version == PROTOCOL_VERSION => name,
+        Some(Message::Hello { version, .. }) => {
+            let reason = format!(
+                "The host speaks protocol version {} but you speak {}",
+                PROTOCOL_VERSION, version
+            );
+            return send(&mut writer, &Message::Rejected { reason });
+        }
+        _ => return Ok(()),
+    };
+    let (outbox, messages) = mpsc::channel();
+    let player = {
+        let mut shared = lock(&shared);
+        if shared.started {
+            drop(shared);
+            let reason = "The race has already started".to_string();
+            return send(&mut writer, &Message::Rejected { reason });
+        }
+        let player = shared.players.len();
+        // queued before the player joins, so it goes ahead of any lanes
+        let welcome = Message::Welcome {
+            version: PROTOCOL_VERSION,
+            player,
+            source: shared.source.clone(),
+        };
+        let _ = outbox.send(welcome);
+        shared.players.push(Player {
+            lane: LaneInfo {
+                name,
+                ..LaneInfo::default()
+            },
+            outbox: Some(outbox),
+        });
+        player
+    };
+    thread::spawn(move || write(writer, messages));
+    while let Ok(Some(message)) = receive(&mut reader) {
+        if let Message::Progress { lane } = message {
+            let mut shared = lock(&shared);
+            let name = std::mem::take(&mut shared.players[player].lane.name);
+            shared.players[player].lane = LaneInfo { name, ..lane };
+        }
+    }
+    let mut shared = lock(&shared);
+    shared.players[player].lane.done = true;
+    shared.players[player].outbox = None;
+    Ok(())
+}
+
+/// Writes a client's messages as they are queued, until the client has gone.
+/// A client that can't keep up is hung up on, which ends its race.
+fn write(mut stream: TcpStream, messages: Receiver<Message>) {
+    for message in messages {
+        if send(&mut stream, &message).is_err() {
+            let _ = stream.shutdown(Shutdown::Both);
+            return;
+        }
+    }
+}
+
+impl Link for Host {
+    fn is_host(&self) -> bool {
+        true
+    }
+
+    fn start(&mut self, countdown: u64) {
+        let mut shared = lock(&self.shared);
+        shared.started = true;
+        shared.broadcast(&Message::Start { countdown });
+    }
+
+    fn started(&mut self) -> Option<u64> {
+        None
+    }
+
+    fn update(&mut self, lane: LaneInfo) {
+        let mut shared = lock(&self.shared);
+        let name = std::mem::take(&mut shared.players[0].lane.name);
+        shared.players[0].lane = LaneInfo { name, ..lane };
+        let lanes: Vec<_> = shared.players.iter().map(|p| p.lane.clone()).collect();
+        if self.last_sent.as_ref() == Some(&lanes) {
+            return;
+        }
+        shared.broadcast(&Message::Lanes {
+            lanes: lanes.clone(),
+        });
+        self.last_sent = Some(lanes);
+    }
+
+    fn others(&self) -> Vec<LaneInfo> {
+        let shared = lock(&self.shared);
+        shared.players[1..].iter().map(|p| p.lane.clone()).collect()
+    }
+
+    fn disconnected(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,16 @@
+use super::LaneInfo;
+
+/// A connection to the other racers in a networked race.
+pub trait Link {
+    fn is_host(&self) -> bool;
+    /// Starts the countdown to the race. Only the host can do this.
+    fn start(&mut self, countdown: u64);
+    /// The countdown, once, when the host has started the race.
+    fn started(&mut self) -> Option<u64>;
+    /// Shares our own progress.
+    fn update(&mut self, lane: LaneInfo);
+    /// Everyone else's progress, as last heard.
+    fn others(&self) -> Vec<LaneInfo>;
+    /// Whether we have lost touch with the host.
+    fn disconnected(&self) -> bool;
+}
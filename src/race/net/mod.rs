@@ -0,0 +1,20 @@
+#[cfg(test)]
+mod tests;
+
+mod client;
+mod host;
+mod link;
+mod protocol;
+
+pub use client::Client;
+pub use host::Host;
+pub use link::Link;
+pub use protocol::LaneInfo;
+
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// Locks state shared with a race's network threads, carrying on with it
+/// even if one of them panicked while holding it.
+fn lock<T>(shared: &Mutex<T>) -> MutexGuard<'_, T> {
+    shared.lock().unwrap_or_else(PoisonError::into_inner)
+}
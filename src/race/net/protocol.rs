@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, Write};
+
+/// Bumped whenever a message changes shape. Hosts reject clients which
+/// speak a different version.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// A racer's progress, as shared between host and clients.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LaneInfo {
+    pub name: String,
+    pub position: usize,
+    /// When the racer completed the text, if they have.
+    pub time_ms: Option<u64>,
+    /// Whether the racer has stopped, by finishing, failing or leaving.
+    pub done: bool,
+}
+
+/// Messages are sent as one JSON object per line.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Message {
+    /// Client to host, on connecting.
+    Hello { version: u32, name: String },
+    /// Host to client, accepting it into the race.
+    Welcome {
+        version: u32,
+        player: usize,
+        /// The klata text file content to race on.
+        source: String,
+    },
+    /// Host to client, refusing it.
+    Rejected { reason: String },
+    /// Host to clients, starting the countdown to the race.
+    Start { countdown: u64 },
+    /// Client to host, reporting the client's progress.
+    Progress { lane: LaneInfo },
+    /// Host to clients, sharing every racer's progress.
+    Lanes { lanes: Vec<LaneInfo> },
+}
+
+pub fn send<W: Write>(writer: &mut W, message: &Message) -> io::Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    writer.write_all(&line)?;
+    writer.flush()
+}
+
+/// Reads the next message, or `None` once the other end has hung up.
+pub fn receive<R: BufRead>(reader: &mut R) -> io::Result<Option<Message>> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&line)?))
+}
@@ -0,0 +1,192 @@
+use super::protocol::{receive, send, Message, PROTOCOL_VERSION};
+use super::*;
+use std::{
+    io::BufReader,
+    net::TcpStream,
+    thread,
+    time::{Duration, Instant},
+};
+
+const SOURCE: &str = "<klata_text><text>race me</text></klata_text>";
+
+fn host() -> Host {
+    Host::bind("127.0.0.1:0", "host", SOURCE).unwrap()
+}
+
+/// Polls `f` until it holds, failing the test after a couple of seconds.
+fn eventually<F: FnMut() -> bool>(mut f: F) {
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while !f() {
+        assert!(Instant::now() < deadline, "timed out");
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn messages_round_trip_as_json_lines() {
+    let message = Message::Progress {
+        lane: LaneInfo {
+            name: "ada".to_string(),
+            position: 4,
+            time_ms: Some(1200),
+            done: true,
+        },
+    };
+    let mut buffer = Vec::new();
+    send(&mut buffer, &message).unwrap();
+    assert_eq!(buffer.last(), Some(&b'\n'));
+    assert_eq!(receive(&mut &buffer[..]).unwrap(), Some(message));
+    assert_eq!(receive(&mut &b""[..]).unwrap(), None);
+}
+
+#[test]
+fn client_is_welcomed_with_the_text() {
+    let host = host();
+    let client = Client::connect(host.address(), "guest").unwrap();
+    assert_eq!(client.source(), SOURCE);
+}
+
+#[test]
+fn progress_is_relayed_both_ways() {
+    let mut host = host();
+    let mut client = Client::connect(host.address(), "guest").unwrap();
+    client.update(LaneInfo {
+        position: 3,
+        ..LaneInfo::default()
+    });
+    eventually(|| host.others().first().map(|lane| lane.position) == Some(3));
+    assert_eq!(host.others()[0].name, "guest");
+
+    host.update(LaneInfo {
+        position: 5,
+        ..LaneInfo::default()
+    });
+    eventually(|| client.others().first().map(|lane| lane.position) == Some(5));
+    assert_eq!(client.others().len(), 1);
+    assert_eq!(client.others()[0].name, "host");
+}
+
+#[test]
+fn clients_see_the_start() {
+    let mut host = host();
+    let mut client = Client::connect(host.address(), "guest").unwrap();
+    eventually(|| host.others().len() == 1);
+    host.start(3);
+    eventually(|| client.started() == Some(3));
+    assert_eq!(client.started(), None);
+}
+
+#[test]
+fn other_protocol_versions_are_rejected() {
+    let host = host();
+    let mut stream = TcpStream::connect(host.address()).unwrap();
+    let hello = Message::Hello {
+        version: PROTOCOL_VERSION + 1,
+        name: "future".to_string(),
+    };
+    send(&mut stream, &hello).unwrap();
+    let mut reader = BufReader::new(stream);
+    assert!(matches!(
+        receive(&mut reader).unwrap(),
+        Some(Message::Rejected { .. })
+    ));
+    assert!(host.others().is_empty());
+}
+
+#[test]
+fn late_joiners_are_rejected() {
+    let mut host = host();
+    host.start(3);
+    assert!(Client::connect(host.address(), "late").is_err());
+}
+
+#[test]
+fn clients_notice_the_host_going() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let host = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        receive(&mut reader).unwrap();
+        let welcome = Message::Welcome {
+            version: PROTOCOL_VERSION,
+            player: 1,
+            source: SOURCE.to_string(),
+        };
+        send(&mut stream, &welcome).unwrap();
+        let lanes = vec![LaneInfo::default(), LaneInfo::default()];
+        send(&mut stream, &Message::Lanes { lanes }).unwrap();
+    });
+    let client = Client::connect(address, "guest").unwrap();
+    host.join().unwrap();
+    eventually(|| client.disconnected());
+    assert_eq!(client.others().len(), 1);
+    assert!(client.others()[0].done);
+}
+
+/// Connects a client by hand, returning a reader of what the host sends
+/// after the welcome.
+fn connect_raw(host: &Host) -> BufReader<TcpStream> {
+    let mut stream = TcpStream::connect(host.address()).unwrap();
+    let hello = Message::Hello {
+        version: PROTOCOL_VERSION,
+        name: "raw".to_string(),
+    };
+    send(&mut stream, &hello).unwrap();
+    let mut reader = BufReader::new(stream);
+    assert!(matches!(
+        receive(&mut reader).unwrap(),
+        Some(Message::Welcome { .. })
+    ));
+    reader
+}
+
+#[test]
+fn unchanged_lanes_are_not_sent_again() {
+    let mut host = host();
+    let mut reader = connect_raw(&host);
+    eventually(|| host.others().len() == 1);
+    for position in [5, 5, 6] {
+        host.update(LaneInfo {
+            position,
+            ..LaneInfo::default()
+        });
+    }
+    let positions = (0..2)
+        .map(|_| match receive(&mut reader).unwrap() {
+            Some(Message::Lanes { lanes }) => lanes[0].position,
+            other => panic!("expected lanes, got {:?}", other),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(positions, vec![5, 6]);
+}
+
+#[test]
+fn a_client_that_stops_reading_does_not_hold_up_the_host() {
+    // long enough that the lanes soon fill the socket's buffers
+    let name = "host".repeat(4096);
+    let mut host = Host::bind("127.0.0.1:0", &name, SOURCE).unwrap();
+    let _reader = connect_raw(&host);
+    eventually(|| host.others().len() == 1);
+    let start = Instant::now();
+    for position in 0..1_000 {
+        host.update(LaneInfo {
+            position,
+            ..LaneInfo::default()
+        });
+    }
+    assert!(start.elapsed() < Duration::from_millis(500));
+}
+
+#[test]
+fn shared_state_outlives_a_panicking_thread() {
+    let shared = std::sync::Arc::new(std::sync::Mutex::new(3));
+    let poisoner = shared.clone();
+    let _ = thread::spawn(move || {
+        let _guard = poisoner.lock().unwrap();
+        panic!("a racer's thread went down");
+    })
+    .join();
+    assert!(shared.is_poisoned());
+    assert_eq!(*lock(&shared), 3);
+}
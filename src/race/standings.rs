@@ -0,0 +1,26 @@
+use std::time::Duration;
+
+/// A racer's place at the end of a race.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Standing {
+    pub name: String,
+    /// When the racer finished, or `None` if they did not.
+    pub time: Option<Duration>,
+}
+
+impl Standing {
+    /// Orders racers by finishing time, with those who did not finish last.
+    pub fn rank(mut standings: Vec<Standing>) -> Vec<Standing> {
+        standings.sort_by_key(|s| (s.time.is_none(), s.time));
+        standings
+    }
+}
+
+impl std::fmt::Display for Standing {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.time {
+            Some(time) => write!(f, "{} {:.1}s", self.name, time.as_secs_f64()),
+            None => write!(f, "{} did not finish", self.name),
+        }
+    }
+}
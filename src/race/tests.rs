@@ -0,0 +1,83 @@
+use super::*;
+use std::time::Duration;
+
+const SOURCE: &str =
+    "<klata_text><text>the quick brown fox jumps over the lazy dog</text></klata_text>";
+
+fn profile(s: &str) -> BotProfile {
+    s.parse().unwrap()
+}
+
+#[test]
+fn presets_parse_by_name() {
+    for name in BotProfile::PRESETS {
+        assert_eq!(profile(name).name, name);
+    }
+}
+
+#[test]
+fn custom_profile_fills_in_missing_fields() {
+    let bot = profile("Ann:60:0.9");
+    assert_eq!(bot.name, "Ann");
+    assert_eq!(bot.wpm, 60.0);
+    assert_eq!(bot.accuracy, 0.9);
+    assert_eq!(bot.burstiness, profile("average").burstiness);
+}
+
+#[test]
+fn malformed_profiles_are_rejected() {
+    assert!("Ann".parse::<BotProfile>().is_err());
+    assert!("Ann:fast".parse::<BotProfile>().is_err());
+    assert!(":60".parse::<BotProfile>().is_err());
+}
+
+#[test]
+fn out_of_range_profiles_are_rejected() {
+    for bot in ["Ann:NaN", "Ann:inf", "Ann:1e-300", "Ann:0", "Ann:-60"] {
+        assert!(bot.parse::<BotProfile>().is_err(), "{}", bot);
+    }
+    for bot in ["Ann:60:1.5", "Ann:60:0.9:-0.1", "Ann:60:0.9:0.2:NaN"] {
+        assert!(bot.parse::<BotProfile>().is_err(), "{}", bot);
+    }
+    assert!("Ann:60:1:0:0".parse::<BotProfile>().is_ok());
+}
+
+#[test]
+fn bots_are_reproducible_from_their_seed() {
+    let a = Bot::new(profile("average"), SOURCE, 7).unwrap();
+    let b = Bot::new(profile("average"), SOURCE, 7).unwrap();
+    let c = Bot::new(profile("average"), SOURCE, 8).unwrap();
+    assert_eq!(a.finish_time(), b.finish_time());
+    assert_ne!(a.finish_time(), c.finish_time());
+}
+
+#[test]
+fn steady_accurate_bot_types_at_its_wpm() {
+    let bot = Bot::new(profile("Metronome:60:1:0:0"), SOURCE, 0).unwrap();
+    // 43 characters at 5 characters a second
+    let time = bot.finish_time().unwrap().as_secs_f64();
+    assert!((time - 8.6).abs() < 0.01, "{}", time);
+    assert_eq!(bot.position(Duration::from_secs(1)), 5);
+}
+
+#[test]
+fn sloppy_bot_still_finishes() {
+    let bot = Bot::new(profile("Sloppy:60:0:1:1"), SOURCE, 3).unwrap();
+    assert!(bot.finish_time().is_some());
+}
+
+#[test]
+fn standings_rank_finishers_before_non_finishers() {
+    let standing = |name: &str, time: Option<u64>| Standing {
+        name: name.to_string(),
+        time: time.map(Duration::from_secs),
+    };
+    let ranked = Standing::rank(vec![
+        standing("a", None),
+        standing("b", Some(20)),
+        standing("c", Some(10)),
+    ]);
+    let names = ranked.iter().map(|s| s.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["c", "b", "a"]);
+    assert_eq!(ranked[2].to_string(), "a did not finish");
+}
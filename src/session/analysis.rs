@@ -0,0 +1,200 @@
+use super::{Key, KeystrokeLog};
+use crate::layout::{Finger, Hand, KeyPosition, Layout};
+use crate::text_model::TextModel;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// How often a finger was used, how often it slipped, and how long it
+/// took to reach its keys.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FingerStats {
+    pub presses: u32,
+    pub errors: u32,
+    /// The total time between the previous key and this finger's, over
+    /// `timed` presses. Totals rather than means, so that sessions add up.
+    pub latency_ms: u64,
+    pub timed: u32,
+}
+
+impl FingerStats {
+    /// The mean time taken to press a key, in milliseconds.
+    pub fn latency(&self) -> Option<f64> {
+        Some(self.latency_ms as f64 / self.timed as f64).filter(|_| self.timed > 0)
+    }
+
+    fn add(&mut self, other: &FingerStats) {
+        self.presses += other.presses;
+        self.errors += other.errors;
+        self.latency_ms += other.latency_ms;
+        self.timed += other.timed;
+    }
+}
+
+/// Pairs of keys typed one after the other, and the total time taken for
+/// the second of each pair.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bigrams {
+    pub count: u32,
+    pub latency_ms: u64,
+}
+
+impl Bigrams {
+    pub fn latency(&self) -> Option<f64> {
+        Some(self.latency_ms as f64 / self.count as f64).filter(|_| self.count > 0)
+    }
+
+    fn push(&mut self, latency_ms: u64) {
+        self.count += 1;
+        self.latency_ms += latency_ms;
+    }
+
+    fn add(&mut self, other: &Bigrams) {
+        self.count += other.count;
+        self.latency_ms += other.latency_ms;
+    }
+}
+
+/// What a session says about each finger and hand, found by looking up
+/// the characters of the text on a layout.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Analysis {
+    pub fingers: BTreeMap<Finger, FingerStats>,
+    /// Different keys pressed one after the other by the same finger.
+    pub same_finger: Bigrams,
+    /// Keys pressed one after the other by one hand, two or more rows apart.
+    pub row_jumps: Bigrams,
+    /// Every other pair of keys, to compare the others with.
+    pub other_bigrams: Bigrams,
+}
+
+impl Analysis {
+    /// Analyses the keystrokes of `log`, typed against `text`, on `layout`.
+    /// Each key is put down to the finger that should have pressed it.
+    pub fn new(text: &TextModel, log: &KeystrokeLog, layout: &Layout) -> Analysis {
+        let chars = text.characters().map(|c| c.value()).collect::<Vec<_>>();
+        let mut analysis = Analysis::default();
+        let mut previous: Option<(KeyPosition, u64)> = None;
+        for keystroke in &log.keystrokes {
+            let typed = match keystroke.key {
+                Key::Char(c) => c,
+                // corrections break the flow from one key to the next
+                Key::Backspace => {
+                    previous = None;
+                    continue;
+                }
+            };
+            let expected = chars.get(keystroke.cursor_before).copied();
+            let position = match expected.and_then(|c| layout.locate(c)) {
+                Some(position) => position,
+                None => {
+                    previous = None;
+                    continue;
+                }
+            };
+            let stats = analysis.fingers.entry(position.finger).or_default();
+            stats.presses += 1;
+            if Some(typed) != expected {
+                stats.errors += 1;
+            }
+            if let Some((before, at_ms)) = previous {
+                let latency_ms = keystroke.at_ms.saturating_sub(at_ms);
+                stats.latency_ms += latency_ms;
+                stats.timed += 1;
+                analysis.bigram(before, position, latency_ms);
+            }
+            previous = Some((position, keystroke.at_ms));
+        }
+        analysis
+    }
+
+    fn bigram(&mut self, from: KeyPosition, to: KeyPosition, latency_ms: u64) {
+        let same_key = (from.row, from.column) == (to.row, to.column);
+        let same_finger = from.finger == to.finger && to.finger != Finger::Thumb && !same_key;
+        let same_hand = from.finger.hand().is_some() && from.finger.hand() == to.finger.hand();
+        let row_jump = same_hand && from.row.abs_diff(to.row) >= 2;
+        if same_finger {
+            self.same_finger.push(latency_ms);
+        }
+        if row_jump {
+            self.row_jumps.push(latency_ms);
+        }
+        if !same_finger && !row_jump {
+            self.other_bigrams.push(latency_ms);
+        }
+    }
+
+    /// Adds another session's analysis to this one.
+    pub fn add(&mut self, other: &Analysis) {
+        for (finger, stats) in &other.fingers {
+            self.fingers.entry(*finger).or_default().add(stats);
+        }
+        self.same_finger.add(&other.same_finger);
+        self.row_jumps.add(&other.row_jumps);
+        self.other_bigrams.add(&other.other_bigrams);
+    }
+
+    /// The share of keys pressed by the left hand and the right, leaving
+    /// out the thumbs.
+    pub fn hand_balance(&self) -> Option<(f64, f64)> {
+        let presses = |hand| {
+            self.fingers
+                .iter()
+                .filter(|(finger, _)| finger.hand() == Some(hand))
+                .map(|(_, stats)| stats.presses)
+                .sum::<u32>() as f64
+        };
+        let (left, right) = (presses(Hand::Left), presses(Hand::Right));
+        Some((left / (left + right), right / (left + right))).filter(|_| left + right > 0.0)
+    }
+
+    /// The finger slowest to press its keys, and its mean latency.
+    pub fn slowest(&self) -> Option<(Finger, f64)> {
+        self.fingers
+            .iter()
+            .filter_map(|(finger, stats)| Some((*finger, stats.latency()?)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// The finger with the most errors, if any finger made one.
+    pub fn most_errors(&self) -> Option<(Finger, u32)> {
+        self.fingers
+            .iter()
+            .map(|(finger, stats)| (*finger, stats.errors))
+            .filter(|(_, errors)| *errors > 0)
+            .max_by_key(|(_, errors)| *errors)
+    }
+
+    /// A line for each finding worth reporting.
+    pub fn summary(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some((left, right)) = self.hand_balance() {
+            lines.push(format!(
+                "Hands: {:.0}% left, {:.0}% right",
+                left * 100.0,
+                right * 100.0
+            ));
+        }
+        if let Some((finger, latency)) = self.slowest() {
+            lines.push(format!("Slowest finger: {}, {:.0} ms", finger, latency));
+        }
+        if let Some((finger, errors)) = self.most_errors() {
+            lines.push(format!("Most errors: {}, {}", finger, errors));
+        }
+        let others = self.other_bigrams.latency();
+        for (name, bigrams) in [
+            ("Same-finger bigrams", &self.same_finger),
+            ("Row jumps", &self.row_jumps),
+        ] {
+            if let (Some(latency), Some(others)) = (bigrams.latency(), others) {
+                lines.push(format!(
+                    "{}: {} at {:.0} ms, others {:.0} ms",
+                    name, bigrams.count, latency, others
+                ));
+            }
+        }
+        lines
+    }
+}
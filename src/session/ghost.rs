@@ -0,0 +1,30 @@
+use super::KeystrokeLog;
+use std::time::Duration;
+
+/// The progress of a previous run through a text, for racing against.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Ghost {
+    /// The cursor position after each keystroke, in time order.
+    positions: Vec<(Duration, usize)>,
+}
+
+impl Ghost {
+    pub fn new(log: &KeystrokeLog) -> Ghost {
+        Ghost {
+            positions: log
+                .keystrokes
+                .iter()
+                .map(|k| (Duration::from_millis(k.at_ms), k.cursor_after))
+                .collect(),
+        }
+    }
+
+    /// Where the ghost's cursor was at `elapsed` into its run.
+    pub fn position(&self, elapsed: Duration) -> usize {
+        let index = self.positions.partition_point(|(at, _)| *at <= elapsed);
+        match index {
+            0 => 0,
+            i => self.positions[i - 1].1,
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use crate::text_model::{CharacterStatus, Correction, TextModel};
+use serde::{Deserialize, Serialize};
+use std::{boxed::Box, error::Error, path::Path, time::Duration};
+
+/// An input which changes the text.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Key {
+    Char(char),
+    Backspace,
+}
+
+impl Key {
+    pub fn apply(self, text: &mut TextModel) {
+        match self {
+            Key::Char(c) => text.type_character(c),
+            Key::Backspace => text.backspace(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Keystroke {
+    pub key: Key,
+    /// Active session time at which the key was pressed.
+    pub at_ms: u64,
+    pub cursor_before: usize,
+    pub cursor_after: usize,
+    /// The status of the character typed or erased, after the key.
+    pub status: Option<CharacterStatus>,
+}
+
+/// Every key pressed in a session, along with the text it was typed
+/// against, so the session can be replayed.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeystrokeLog {
+    /// The text file content the session was typed against.
+    pub source: String,
+    pub correction: Correction,
+    pub keystrokes: Vec<Keystroke>,
+}
+
+impl KeystrokeLog {
+    pub fn new(source: &str, correction: Correction) -> KeystrokeLog {
+        KeystrokeLog {
+            source: source.to_string(),
+            correction,
+            keystrokes: Vec::new(),
+        }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<KeystrokeLog, Box<dyn Error>> {
+        Ok(serde_json::from_slice(&std::fs::read(path)?)?)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn Error>> {
+        std::fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// A fresh text model to replay the keystrokes against.
+    pub fn text_model(&self) -> Result<TextModel, Box<dyn Error>> {
+        let mut text = TextModel::from_string(&self.source)?;
+        text.set_correction(self.correction);
+        Ok(text)
+    }
+
+    /// Applies `key` to `text` and logs it as pressed at `elapsed`.
+    pub fn press(&mut self, key: Key, text: &mut TextModel, elapsed: Duration) {
+        let cursor_before = text.cursor();
+        key.apply(text);
+        let cursor_after = text.cursor();
+        self.keystrokes.push(Keystroke {
+            key,
+            at_ms: elapsed.as_millis() as u64,
+            cursor_before,
+            cursor_after,
+            status: text
+                .characters()
+                .nth(cursor_before.min(cursor_after))
+                .map(|c| c.status()),
+        });
+    }
+
+    /// How long the session took to complete the text, or `None` if it
+    /// was never completed.
+    pub fn finish_time(&self) -> Result<Option<Duration>, Box<dyn Error>> {
+        let mut text = self.text_model()?;
+        self.keystrokes.iter().for_each(|k| k.key.apply(&mut text));
+        Ok(match text.is_complete() {
+            true => Some(self.duration()),
+            false => None,
+        })
+    }
+
+    /// How long the logged session ran for, up to its last keystroke.
+    pub fn duration(&self) -> Duration {
+        self.keystrokes
+            .last()
+            .map_or(Duration::ZERO, |k| Duration::from_millis(k.at_ms))
+    }
+}
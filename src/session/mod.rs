@@ -0,0 +1,20 @@
+#[cfg(test)]
+mod tests;
+
+mod analysis;
+mod ghost;
+mod keystroke_log;
+mod pacer;
+mod rules;
+mod settings;
+mod stats;
+mod stopwatch;
+
+pub use analysis::Analysis;
+pub use ghost::Ghost;
+pub use keystroke_log::{Key, KeystrokeLog};
+pub use pacer::Pacer;
+pub use rules::{Failure, Rules};
+pub use settings::{GhostRun, Settings, Start};
+pub use stats::Stats;
+pub use stopwatch::Stopwatch;
@@ -0,0 +1,15 @@
+use std::time::Duration;
+
+/// Moves through the text at a steady target speed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pacer {
+    pub wpm: f64,
+}
+
+impl Pacer {
+    /// How many characters the pacer has covered after `elapsed`,
+    /// counting five characters as a word.
+    pub fn position(&self, elapsed: Duration) -> usize {
+        (elapsed.as_secs_f64() * self.wpm * 5.0 / 60.0) as usize
+    }
+}
@@ -0,0 +1,68 @@
+use super::Stats;
+use std::time::Duration;
+
+/// Accuracy is not checked until this many characters have been typed.
+const ACCURACY_GRACE: usize = 10;
+/// Speed is not checked until the session has been running this long.
+const WPM_GRACE: Duration = Duration::from_secs(5);
+
+/// Conditions under which a session fails before the text is finished.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Rules {
+    /// Fail on the first wrong character.
+    pub sudden_death: bool,
+    pub max_errors: Option<u32>,
+    /// Minimum accuracy, as a proportion between 0 and 1.
+    pub min_accuracy: Option<f64>,
+    pub min_wpm: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Failure {
+    SuddenDeath,
+    TooManyErrors(u32),
+    AccuracyTooLow(f64),
+    WpmTooLow(f64),
+}
+
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Failure::SuddenDeath => write!(f, "Sudden death: a character was typed wrong"),
+            Failure::TooManyErrors(errors) => write!(f, "Too many errors ({})", errors),
+            Failure::AccuracyTooLow(accuracy) => {
+                write!(f, "Accuracy fell to {:.1}%", accuracy * 100.0)
+            }
+            Failure::WpmTooLow(wpm) => write!(f, "Speed fell to {:.1} WPM", wpm),
+        }
+    }
+}
+
+impl Rules {
+    /// Checks the stats of a running session against the rules.
+    ///
+    /// Accuracy and speed are noisy at the start of a session, so they are
+    /// only checked after a grace period, or once the text is complete.
+    pub fn check(&self, stats: &Stats) -> Option<Failure> {
+        if self.sudden_death && stats.errors > 0 {
+            return Some(Failure::SuddenDeath);
+        }
+        if let Some(max_errors) = self.max_errors {
+            if stats.errors > max_errors {
+                return Some(Failure::TooManyErrors(stats.errors));
+            }
+        }
+        if let Some(min_accuracy) = self.min_accuracy {
+            if (stats.completed || stats.typed >= ACCURACY_GRACE) && stats.accuracy() < min_accuracy
+            {
+                return Some(Failure::AccuracyTooLow(stats.accuracy()));
+            }
+        }
+        if let Some(min_wpm) = self.min_wpm {
+            if (stats.completed || stats.elapsed >= WPM_GRACE) && stats.wpm() < min_wpm {
+                return Some(Failure::WpmTooLow(stats.wpm()));
+            }
+        }
+        None
+    }
+}
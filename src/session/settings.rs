@@ -0,0 +1,66 @@
+use super::{Pacer, Rules};
+use crate::layout::Layout;
+use crate::race::BotProfile;
+use crate::text_model::Correction;
+use std::{path::PathBuf, time::Duration};
+
+/// When the session clock starts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Start {
+    /// The clock starts on the first keystroke.
+    FirstKeystroke,
+    /// A countdown of this many seconds is shown, then the clock starts.
+    Countdown(u64),
+}
+
+/// Which previous run of the text to race against.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GhostRun {
+    /// The fastest completed run in the stored keystroke logs.
+    Best,
+    /// The run in this keystroke log file.
+    File(PathBuf),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    pub start: Start,
+    /// Pause the session after this long without input.
+    pub idle_timeout: Option<Duration>,
+    pub rules: Rules,
+    pub correction: Correction,
+    /// Hide whether typed characters are correct until the results.
+    pub blind: bool,
+    pub ghost: Option<GhostRun>,
+    pub pacer: Option<Pacer>,
+    pub bots: Vec<BotProfile>,
+    /// Seeds the simulation of the bots, the choice of text from a library
+    /// and the drills of lessons.
+    pub seed: u64,
+    /// Show a keyboard in this layout, with the next key highlighted.
+    pub layout: Option<Layout>,
+    /// The layout the system types in. Keys are translated from it to
+    /// `layout`, to practise a layout without switching to it.
+    pub os_layout: Option<Layout>,
+    /// Where the difficulty of texts typed is cached, if anywhere.
+    pub difficulty_index: Option<PathBuf>,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            start: Start::FirstKeystroke,
+            idle_timeout: Some(Duration::from_secs(10)),
+            rules: Rules::default(),
+            correction: Correction::default(),
+            blind: false,
+            ghost: None,
+            pacer: None,
+            bots: Vec::new(),
+            seed: 0,
+            layout: None,
+            os_layout: None,
+            difficulty_index: None,
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use crate::text_model::{CharacterStatus, TextModel};
+use std::time::Duration;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stats {
+    pub elapsed: Duration,
+    pub idle: Duration,
+    pub typed: usize,
+    pub correct: usize,
+    pub corrected: usize,
+    pub errors: u32,
+    pub completed: bool,
+}
+
+impl Stats {
+    pub fn new(text: &TextModel, elapsed: Duration, idle: Duration) -> Stats {
+        let mut stats = Stats {
+            elapsed,
+            idle,
+            completed: text.is_complete(),
+            ..Stats::default()
+        };
+        for c in text.characters() {
+            stats.errors += c.wrong_attempts();
+            match c.status() {
+                CharacterStatus::Untyped => continue,
+                CharacterStatus::Correct => stats.correct += 1,
+                CharacterStatus::Corrected => stats.corrected += 1,
+                CharacterStatus::Wrong => {}
+            }
+            stats.typed += 1;
+        }
+        stats
+    }
+
+    /// Words per minute, counting five correctly typed characters as a word.
+    pub fn wpm(&self) -> f64 {
+        let minutes = self.elapsed.as_secs_f64() / 60.0;
+        if minutes == 0.0 {
+            return 0.0;
+        }
+        (self.correct + self.corrected) as f64 / 5.0 / minutes
+    }
+
+    /// Proportion of typed characters that were right first time.
+    pub fn accuracy(&self) -> f64 {
+        if self.typed == 0 {
+            return 1.0;
+        }
+        self.correct as f64 / self.typed as f64
+    }
+}
@@ -0,0 +1,62 @@
+use std::time::{Duration, Instant};
+
+/// Measures the active typing time of a session.
+///
+/// Time spent paused, whether explicitly or because the typist went idle,
+/// is accumulated separately so it can be excluded from the stats.
+#[derive(Default)]
+pub struct Stopwatch {
+    active: Duration,
+    idle: Duration,
+    running_since: Option<Instant>,
+    paused_since: Option<Instant>,
+}
+
+impl Stopwatch {
+    pub fn start(&mut self, now: Instant) {
+        if !self.is_started() {
+            self.running_since = Some(now);
+        }
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.running_since.is_some() || self.paused_since.is_some()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_since.is_some()
+    }
+
+    /// Pauses the stopwatch as of `at`, which may lie in the past. This lets
+    /// idle detection freeze the clock at the last keystroke.
+    pub fn pause(&mut self, at: Instant) {
+        if let Some(since) = self.running_since.take() {
+            let at = at.max(since);
+            self.active += at - since;
+            self.paused_since = Some(at);
+        }
+    }
+
+    pub fn resume(&mut self, now: Instant) {
+        if let Some(since) = self.paused_since.take() {
+            self.idle += now.saturating_duration_since(since);
+            self.running_since = Some(now);
+        }
+    }
+
+    pub fn elapsed(&self, now: Instant) -> Duration {
+        self.active + since(self.running_since, now)
+    }
+
+    pub fn idle(&self, now: Instant) -> Duration {
+        self.idle + since(self.paused_since, now)
+    }
+}
+
+fn since(instant: Option<Instant>, now: Instant) -> Duration {
+    instant.map_or(Duration::ZERO, |i| now.saturating_duration_since(i))
+}
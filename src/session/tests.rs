@@ -0,0 +1,295 @@
+use super::*;
+use crate::text_model::{CharacterStatus, TextModel};
+use std::time::{Duration, Instant};
+
+fn secs(s: u64) -> Duration {
+    Duration::from_secs(s)
+}
+
+fn make_text_model() -> TextModel {
+    TextModel::from_string("<klata_text><text>abcde fghij</text></klata_text>").unwrap()
+}
+
+#[test]
+fn stopwatch_not_started_has_no_elapsed_time() {
+    let stopwatch = Stopwatch::default();
+    assert!(!stopwatch.is_started());
+    assert_eq!(stopwatch.elapsed(Instant::now()), Duration::ZERO);
+}
+
+#[test]
+fn stopwatch_measures_time_since_start() {
+    let t0 = Instant::now();
+    let mut stopwatch = Stopwatch::default();
+    stopwatch.start(t0);
+    assert_eq!(stopwatch.elapsed(t0 + secs(3)), secs(3));
+}
+
+#[test]
+fn paused_time_is_excluded_and_counted_as_idle() {
+    let t0 = Instant::now();
+    let mut stopwatch = Stopwatch::default();
+    stopwatch.start(t0);
+    stopwatch.pause(t0 + secs(2));
+    stopwatch.resume(t0 + secs(7));
+    assert_eq!(stopwatch.elapsed(t0 + secs(8)), secs(3));
+    assert_eq!(stopwatch.idle(t0 + secs(8)), secs(5));
+}
+
+#[test]
+fn pausing_in_the_past_freezes_time_at_that_point() {
+    let t0 = Instant::now();
+    let mut stopwatch = Stopwatch::default();
+    stopwatch.start(t0);
+    // idle detected at 10s, last keystroke was at 4s
+    stopwatch.pause(t0 + secs(4));
+    assert!(stopwatch.is_paused());
+    assert_eq!(stopwatch.elapsed(t0 + secs(10)), secs(4));
+    assert_eq!(stopwatch.idle(t0 + secs(10)), secs(6));
+}
+
+#[test]
+fn restarting_a_started_stopwatch_does_nothing() {
+    let t0 = Instant::now();
+    let mut stopwatch = Stopwatch::default();
+    stopwatch.start(t0);
+    stopwatch.start(t0 + secs(5));
+    assert_eq!(stopwatch.elapsed(t0 + secs(6)), secs(6));
+}
+
+#[test]
+fn stats_count_character_statuses() {
+    let mut text = make_text_model();
+    text.type_character('a');
+    text.type_character('x');
+    text.backspace();
+    text.type_character('b');
+    text.type_character('x');
+    let stats = Stats::new(&text, secs(60), Duration::ZERO);
+    assert_eq!(stats.typed, 3);
+    assert_eq!(stats.correct, 1);
+    assert_eq!(stats.corrected, 1);
+    assert_eq!(stats.errors, 2);
+    assert!(!stats.completed);
+}
+
+#[test]
+fn wpm_counts_five_correct_characters_as_a_word() {
+    let mut text = make_text_model();
+    "abcde fghij".chars().for_each(|c| text.type_character(c));
+    let stats = Stats::new(&text, secs(30), secs(100));
+    assert!(stats.completed);
+    assert_eq!(stats.wpm(), 4.4);
+    assert_eq!(stats.accuracy(), 1.0);
+}
+
+#[test]
+fn stats_without_time_have_zero_wpm() {
+    let stats = Stats::new(&make_text_model(), Duration::ZERO, Duration::ZERO);
+    assert_eq!(stats.wpm(), 0.0);
+}
+
+fn stats(typed: usize, correct: usize, errors: u32, elapsed: Duration) -> Stats {
+    Stats {
+        elapsed,
+        typed,
+        correct,
+        errors,
+        ..Stats::default()
+    }
+}
+
+#[test]
+fn default_rules_never_fail() {
+    assert_eq!(Rules::default().check(&stats(10, 0, 10, secs(60))), None);
+}
+
+#[test]
+fn sudden_death_fails_on_first_error() {
+    let rules = Rules {
+        sudden_death: true,
+        ..Rules::default()
+    };
+    assert_eq!(rules.check(&stats(1, 1, 0, secs(1))), None);
+    assert_eq!(
+        rules.check(&stats(2, 1, 1, secs(1))),
+        Some(Failure::SuddenDeath)
+    );
+}
+
+#[test]
+fn max_errors_fails_when_exceeded() {
+    let rules = Rules {
+        max_errors: Some(2),
+        ..Rules::default()
+    };
+    assert_eq!(rules.check(&stats(5, 3, 2, secs(1))), None);
+    assert_eq!(
+        rules.check(&stats(5, 2, 3, secs(1))),
+        Some(Failure::TooManyErrors(3))
+    );
+}
+
+#[test]
+fn min_accuracy_waits_for_grace_period() {
+    let rules = Rules {
+        min_accuracy: Some(0.9),
+        ..Rules::default()
+    };
+    assert_eq!(rules.check(&stats(5, 4, 1, secs(1))), None);
+    assert_eq!(
+        rules.check(&stats(10, 8, 2, secs(1))),
+        Some(Failure::AccuracyTooLow(0.8))
+    );
+}
+
+#[test]
+fn min_wpm_waits_for_grace_period() {
+    let rules = Rules {
+        min_wpm: Some(30.0),
+        ..Rules::default()
+    };
+    assert_eq!(rules.check(&stats(5, 5, 0, secs(2))), None);
+    assert_eq!(
+        rules.check(&stats(10, 10, 0, secs(60))),
+        Some(Failure::WpmTooLow(2.0))
+    );
+}
+
+#[test]
+fn failure_reasons_are_readable() {
+    assert_eq!(
+        Failure::AccuracyTooLow(0.875).to_string(),
+        "Accuracy fell to 87.5%"
+    );
+}
+
+const SOURCE: &str = "<klata_text><text>abcde fghij</text></klata_text>";
+
+#[test]
+fn keystroke_log_records_cursor_and_status() {
+    let mut text = make_text_model();
+    let mut log = KeystrokeLog::new(SOURCE, text.correction());
+    log.press(Key::Char('x'), &mut text, Duration::from_millis(100));
+    log.press(Key::Backspace, &mut text, Duration::from_millis(250));
+    let keystrokes = &log.keystrokes;
+    assert_eq!(keystrokes[0].at_ms, 100);
+    assert_eq!(keystrokes[0].cursor_before, 0);
+    assert_eq!(keystrokes[0].cursor_after, 1);
+    assert_eq!(keystrokes[0].status, Some(CharacterStatus::Wrong));
+    assert_eq!(keystrokes[1].cursor_after, 0);
+    assert_eq!(keystrokes[1].status, Some(CharacterStatus::Untyped));
+    assert_eq!(log.duration(), Duration::from_millis(250));
+}
+
+#[test]
+fn replaying_a_keystroke_log_reproduces_the_text() {
+    let mut text = make_text_model();
+    let mut log = KeystrokeLog::new(SOURCE, text.correction());
+    for key in [Key::Char('a'), Key::Char('x'), Key::Backspace, Key::Char('b')] {
+        log.press(key, &mut text, Duration::ZERO);
+    }
+    let mut replayed = log.text_model().unwrap();
+    log.keystrokes.iter().for_each(|k| k.key.apply(&mut replayed));
+    assert_eq!(replayed.cursor(), text.cursor());
+    assert_eq!(
+        Stats::new(&replayed, secs(1), Duration::ZERO),
+        Stats::new(&text, secs(1), Duration::ZERO)
+    );
+}
+
+#[test]
+fn keystroke_log_survives_a_round_trip_through_a_file() {
+    let mut text = make_text_model();
+    let mut log = KeystrokeLog::new(SOURCE, text.correction());
+    log.press(Key::Char('a'), &mut text, Duration::from_millis(42));
+    let path = std::env::temp_dir().join(format!("klata-log-{}.json", std::process::id()));
+    log.save(&path).unwrap();
+    let loaded = KeystrokeLog::load(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.unwrap(), log);
+}
+
+#[test]
+fn ghost_follows_logged_cursor_over_time() {
+    let mut text = make_text_model();
+    let mut log = KeystrokeLog::new(SOURCE, text.correction());
+    log.press(Key::Char('a'), &mut text, secs(1));
+    log.press(Key::Char('b'), &mut text, secs(2));
+    log.press(Key::Backspace, &mut text, secs(3));
+    let ghost = Ghost::new(&log);
+    assert_eq!(ghost.position(Duration::ZERO), 0);
+    assert_eq!(ghost.position(secs(1)), 1);
+    assert_eq!(ghost.position(Duration::from_millis(2500)), 2);
+    assert_eq!(ghost.position(secs(10)), 1);
+}
+
+#[test]
+fn incomplete_log_has_no_finish_time() {
+    let mut text = make_text_model();
+    let mut log = KeystrokeLog::new(SOURCE, text.correction());
+    log.press(Key::Char('a'), &mut text, secs(1));
+    assert_eq!(log.finish_time().unwrap(), None);
+    "bcde fghij"
+        .chars()
+        .for_each(|c| log.press(Key::Char(c), &mut text, secs(4)));
+    assert_eq!(log.finish_time().unwrap(), Some(secs(4)));
+}
+
+#[test]
+fn pacer_covers_five_characters_per_word() {
+    let pacer = Pacer { wpm: 60.0 };
+    assert_eq!(pacer.position(Duration::ZERO), 0);
+    assert_eq!(pacer.position(secs(1)), 5);
+    assert_eq!(pacer.position(Duration::from_millis(1100)), 5);
+    assert_eq!(pacer.position(secs(12)), 60);
+}
+
+fn analyse(keys: &[(Key, u64)]) -> Analysis {
+    let source = "<klata_text><text>deck</text></klata_text>";
+    let mut text = TextModel::from_string(source).unwrap();
+    let mut log = KeystrokeLog::new(source, text.correction());
+    for &(key, at_ms) in keys {
+        log.press(key, &mut text, Duration::from_millis(at_ms));
+    }
+    let layout = crate::layout::Layout::built_in("qwerty").unwrap();
+    Analysis::new(&text, &log, &layout)
+}
+
+#[test]
+fn analysis_puts_keys_down_to_fingers() {
+    use crate::layout::Finger;
+    let keys = [
+        (Key::Char('d'), 0),
+        (Key::Char('e'), 100),
+        (Key::Char('x'), 400),
+        (Key::Char('k'), 500),
+    ];
+    let analysis = analyse(&keys);
+    let middle = analysis.fingers[&Finger::LeftMiddle];
+    assert_eq!((middle.presses, middle.errors), (3, 1));
+    assert_eq!(middle.latency(), Some(200.0));
+    assert_eq!(analysis.hand_balance(), Some((0.75, 0.25)));
+    assert_eq!(analysis.slowest(), Some((Finger::LeftMiddle, 200.0)));
+    assert_eq!(analysis.most_errors(), Some((Finger::LeftMiddle, 1)));
+    // e to c is both a same-finger bigram and a jump over the home row
+    assert_eq!(analysis.same_finger.count, 2);
+    assert_eq!(analysis.row_jumps.latency(), Some(300.0));
+    assert_eq!(analysis.other_bigrams.latency(), Some(100.0));
+}
+
+#[test]
+fn corrections_break_the_timing_of_bigrams() {
+    let keys = [
+        (Key::Char('d'), 0),
+        (Key::Char('x'), 100),
+        (Key::Backspace, 200),
+        (Key::Char('e'), 900),
+    ];
+    let mut analysis = analyse(&keys);
+    assert_eq!(analysis.same_finger.count, 1);
+    assert_eq!(analysis.fingers.values().map(|f| f.timed).sum::<u32>(), 1);
+    analysis.add(&analysis.clone());
+    assert_eq!(analysis.same_finger.count, 2);
+    assert_eq!(analysis.fingers.values().map(|f| f.presses).sum::<u32>(), 6);
+}
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+/// What backspace is allowed to erase.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Correction {
+    /// Backspace can erase all the way to the start of the text.
+    #[default]
+    Free,
+    /// Backspace does nothing.
+    Disabled,
+    /// Backspace cannot leave the word under the cursor.
+    Word,
+    /// Backspace cannot erase into a word which was typed correctly.
+    Confidence,
+}
+
+impl Correction {
+    pub const NAMES: [&'static str; 4] = ["free", "disabled", "word", "confidence"];
+}
+
+impl std::fmt::Display for Correction {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            Correction::Free => Correction::NAMES[0],
+            Correction::Disabled => Correction::NAMES[1],
+            Correction::Word => Correction::NAMES[2],
+            Correction::Confidence => Correction::NAMES[3],
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl std::str::FromStr for Correction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Correction, String> {
+        match s {
+            "free" => Ok(Correction::Free),
+            "disabled" => Ok(Correction::Disabled),
+            "word" => Ok(Correction::Word),
+            "confidence" => Ok(Correction::Confidence),
+            _ => Err(format!("Unknown correction policy '{}'", s)),
+        }
+    }
+}
@@ -2,9 +2,11 @@ use quick_xml::{
     Reader,
     events::Event,
 };
+use super::ParseError;
 use std::{
-    boxed::Box,
     error::Error,
+    num::ParseIntError,
+    path::PathBuf,
 };
 
 #[derive(Default, Debug, PartialEq)]
@@ -12,19 +14,88 @@ pub struct Data {
     pub text: String,
     pub author: Option<String>,
     pub date: Option<[u16;3]>,
+    /// Where the text was taken from, such as a book or speech.
+    pub source: Option<String>,
 }
 
 #[derive(Debug)]
-enum DataError {
+pub enum DataError {
+    Xml(quick_xml::Error),
+    BadDate(ParseIntError),
     UnsupportedCharacter(char),
+    // the rest are only found by linting, as the parser lets them through
+    EmptyText,
+    ImpossibleDate([u16;3]),
+    UnknownElement(String),
+    SuspiciousWhitespace(&'static str),
+    /// The same text as in the file at this path, on this line.
+    DuplicateText(PathBuf, usize),
+    /// The file couldn't be read as text.
+    Unreadable(std::io::Error),
+    // found when importing collections of texts
+    Json(serde_json::Error),
+    Csv(&'static str),
+    MissingField(String),
+    // found in gtypist lesson scripts
+    UnknownCommand(char),
+    BadCommand(&'static str),
+    UnknownLabel(String),
+    DuplicateLabel(String),
+}
+
+impl DataError {
+    /// A short name for the kind of problem, for machine-readable output.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DataError::Xml(_) => "xml",
+            DataError::BadDate(_) => "bad-date",
+            DataError::UnsupportedCharacter(_) => "unsupported-character",
+            DataError::EmptyText => "empty-text",
+            DataError::ImpossibleDate(_) => "impossible-date",
+            DataError::UnknownElement(_) => "unknown-element",
+            DataError::SuspiciousWhitespace(_) => "suspicious-whitespace",
+            DataError::DuplicateText(..) => "duplicate-text",
+            DataError::Unreadable(_) => "unreadable",
+            DataError::Json(_) => "json",
+            DataError::Csv(_) => "csv",
+            DataError::MissingField(_) => "missing-field",
+            DataError::UnknownCommand(_) => "unknown-command",
+            DataError::BadCommand(_) => "bad-command",
+            DataError::UnknownLabel(_) => "unknown-label",
+            DataError::DuplicateLabel(_) => "duplicate-label",
+        }
+    }
 }
 
 impl std::fmt::Display for DataError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
+            DataError::Xml(e) => write!(f, "{}", e),
+            DataError::BadDate(e) => write!(f, "Bad date: {}", e),
             DataError::UnsupportedCharacter(c) => {
-                write!(f, "Unsupported character '{}'", c)
+                write!(f, "Unsupported character '{}' (U+{:04X}", c, *c as u32)?;
+                if let Some(name) = super::parse_error::char_name(*c) {
+                    write!(f, " {}", name)?;
+                }
+                write!(f, ")")
+            }
+            DataError::EmptyText => write!(f, "The text is empty"),
+            DataError::ImpossibleDate([d, m, y]) => {
+                write!(f, "Impossible date {}/{}/{}", d, m, y)
+            }
+            DataError::UnknownElement(name) => write!(f, "Unknown element <{}>", name),
+            DataError::SuspiciousWhitespace(what) => write!(f, "Suspicious whitespace: {}", what),
+            DataError::DuplicateText(path, line) => {
+                write!(f, "The same text as {}:{}", path.display(), line)
             }
+            DataError::Unreadable(e) => write!(f, "Can't read the file: {}", e),
+            DataError::Json(e) => write!(f, "{}", e),
+            DataError::Csv(e) => write!(f, "Bad CSV: {}", e),
+            DataError::MissingField(name) => write!(f, "No '{}' field", name),
+            DataError::UnknownCommand(c) => write!(f, "Unknown command '{}:'", c),
+            DataError::BadCommand(e) => write!(f, "Bad command: {}", e),
+            DataError::UnknownLabel(label) => write!(f, "No label '{}'", label),
+            DataError::DuplicateLabel(label) => write!(f, "Label '{}' is used twice", label),
         }
     }
     
@@ -32,53 +103,124 @@ impl std::fmt::Display for DataError {
 
 impl Error for DataError {}
 
+impl From<quick_xml::Error> for DataError {
+    fn from(e: quick_xml::Error) -> DataError {
+        DataError::Xml(e)
+    }
+}
+
+impl From<ParseIntError> for DataError {
+    fn from(e: ParseIntError) -> DataError {
+        DataError::BadDate(e)
+    }
+}
+
 impl Data {
-    pub fn from_string(s: &str) -> Result<Data, Box<dyn Error>> {
+    pub fn from_string(s: &str) -> Result<Data, ParseError> {
+        Ok(Data::all_from_string(s)?.into_iter().next().unwrap_or_default())
+    }
+
+    /// Every text in `s`, which may be a single klata text or a library
+    /// of them.
+    pub fn all_from_string(s: &str) -> Result<Vec<Data>, ParseError> {
+        Ok(Data::located_from_string(s)?.into_iter().map(|(data, _)| data).collect())
+    }
+
+    /// Every text in `s`, with the offset of the `<klata_text>` it is in.
+    pub fn located_from_string(s: &str) -> Result<Vec<(Data, usize)>, ParseError> {
         let mut reader = Reader::from_str(s);
-        let mut data = Data::default();
-        loop {
-            match reader.read_event(&mut Vec::new())? {
-                Event::Start(e) => {
-                    if e.name() == "klata_text".as_bytes() {
-                        data = read_text(&mut reader)?;
-                    }
-                },
-                Event::Eof => break,
-                _ => {},
+        let texts = read(&mut reader)
+            .map_err(|e| ParseError::new(s, reader.buffer_position(), e))?;
+
+        let mut all = Vec::new();
+        for (mut data, start, text_start) in texts {
+            data.text = data.text.trim_start().to_string();
+
+            if let Some(c) = unsupported_character(&data.text) {
+                // the character may have been written as an entity, in which
+                // case point at the start of the text
+                let offset = s[text_start..].find(c).map_or(text_start, |i| text_start + i);
+                return Err(ParseError::new(s, offset, DataError::UnsupportedCharacter(c)));
             }
+            all.push((data, start));
         }
+        Ok(all)
+    }
+
+    /// The data as a klata text file.
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::from("<klata_text>\n");
+        if let Some(author) = &self.author {
+            xml += &format!("  <author>{}</author>\n", escape(author));
+        }
+        if let Some([d, m, y]) = self.date {
+            xml += &format!("  <date><d>{}</d><m>{}</m><y>{}</y></date>\n", d, m, y);
+        }
+        if let Some(source) = &self.source {
+            xml += &format!("  <source>{}</source>\n", escape(source));
+        }
+        xml += &format!("  <text>{}</text>\n</klata_text>\n", escape(&self.text));
+        xml
+    }
+}
 
-        data.text = data.text.trim_start().to_string();
+/// A library of `texts`, to be read by `Data::all_from_string`.
+pub fn library_to_xml(texts: &[Data]) -> String {
+    let mut xml = String::from("<klata_library>\n");
+    for data in texts {
+        xml += &data.to_xml();
+    }
+    xml + "</klata_library>\n"
+}
 
-        if let Some(c) = data.text
-            .chars()
-            .filter(|c| !c.is_ascii_alphanumeric())
-            .filter(|c| !c.is_ascii_whitespace())
-            .filter(|c| !c.is_ascii_punctuation())
-            .next() {
-            return Err(Box::new(DataError::UnsupportedCharacter(c)));
+/// The first character in `text` that klata cannot type.
+pub fn unsupported_character(text: &str) -> Option<char> {
+    text.chars()
+        .filter(|c| !c.is_ascii_alphanumeric())
+        .filter(|c| !c.is_ascii_whitespace())
+        .find(|c| !c.is_ascii_punctuation())
+}
+
+fn escape(s: &str) -> String {
+    String::from_utf8_lossy(&quick_xml::escape::escape(s.as_bytes())).into_owned()
+}
+
+/// Each text in the file, with the offset its `<text>` starts at.
+/// Reads every text, with the offsets of its `<klata_text>` and its text.
+fn read<B: std::io::BufRead>(reader: &mut Reader<B>) -> Result<Vec<(Data, usize, usize)>, DataError> {
+    let mut texts = Vec::new();
+    loop {
+        let start = reader.buffer_position();
+        match reader.read_event(&mut Vec::new())? {
+            Event::Start(e) if e.name() == "klata_text".as_bytes() => {
+                let mut text_start = 0;
+                let data = read_text(reader, &mut text_start)?;
+                texts.push((data, start, text_start));
+            },
+            Event::Eof => break,
+            _ => {},
         }
-            
-        Ok(data)
     }
+    Ok(texts)
 }
 
-fn read_text<B: std::io::BufRead>(reader: &mut Reader<B>) -> Result<Data, Box<dyn Error>> {
+fn read_text<B: std::io::BufRead>(reader: &mut Reader<B>, text_start: &mut usize) -> Result<Data, DataError> {
     let mut data = Data::default();
     loop {
         match reader.read_event(&mut Vec::new())? {
-            Event::End(end_bytes) => {
-                if end_bytes.name() == "klata_text".as_bytes() {
-                    break;
-                }
+            Event::End(end_bytes) if end_bytes.name() == "klata_text".as_bytes() => {
+                break;
             },
             Event::Start(start_bytes) => {
                 if start_bytes.name() == "text".as_bytes() {
+                    *text_start = reader.buffer_position();
                     data.text = reader.read_text(start_bytes.name(), &mut Vec::new())?;
                 } else if start_bytes.name() == "author".as_bytes() {
                     data.author = Some(reader.read_text(start_bytes.name(), &mut Vec::new())?);
                 } else if start_bytes.name() == "date".as_bytes() {
                     data.date = Some(read_date(reader)?);
+                } else if start_bytes.name() == "source".as_bytes() {
+                    data.source = Some(reader.read_text(start_bytes.name(), &mut Vec::new())?);
                 }
             }
             _ => {},
@@ -87,14 +229,12 @@ fn read_text<B: std::io::BufRead>(reader: &mut Reader<B>) -> Result<Data, Box<dy
     Ok(data)
 }
 
-fn read_date<B: std::io::BufRead>(reader: &mut Reader<B>) -> Result<[u16;3], Box<dyn Error>> {
+fn read_date<B: std::io::BufRead>(reader: &mut Reader<B>) -> Result<[u16;3], DataError> {
     let mut date: [u16;3] = [0, 0, 0];
     loop {
         match reader.read_event(&mut Vec::new())? {
-            Event::End(end_bytes) => {
-                if end_bytes.name() == "date".as_bytes() {
-                    break;
-                }
+            Event::End(end_bytes) if end_bytes.name() == "date".as_bytes() => {
+                break;
             },
             Event::Start(start_bytes) => {
                 if start_bytes.name() == "d".as_bytes() {
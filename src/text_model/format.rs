@@ -0,0 +1,291 @@
+use super::data::Data;
+use std::path::Path;
+
+/// The kinds of file a text can be read from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// A klata text file.
+    Xml,
+    /// Plain prose, which is reflowed into paragraphs.
+    Text,
+    /// Markdown, with the markup stripped to leave typeable prose.
+    Markdown,
+    /// A JSON array of records, each a text.
+    Json,
+    /// A CSV file with a header row, each row after it a text.
+    Csv,
+}
+
+impl Format {
+    pub const NAMES: [&'static str; 5] = ["xml", "text", "markdown", "json", "csv"];
+
+    /// Guesses the format from the file extension, or failing that from
+    /// whether the content looks like a klata text file or JSON records.
+    pub fn detect<P: AsRef<Path>>(path: P, content: &str) -> Format {
+        let extension = path.as_ref().extension().and_then(|e| e.to_str());
+        match extension.map(str::to_ascii_lowercase).as_deref() {
+            Some("xml") => Format::Xml,
+            Some("txt") => Format::Text,
+            Some("md" | "markdown") => Format::Markdown,
+            Some("json") => Format::Json,
+            Some("csv") => Format::Csv,
+            _ if content.contains("<klata_text") => Format::Xml,
+            _ if content.trim_start().starts_with("[{") => Format::Json,
+            _ => Format::Text,
+        }
+    }
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            Format::Xml => Format::NAMES[0],
+            Format::Text => Format::NAMES[1],
+            Format::Markdown => Format::NAMES[2],
+            Format::Json => Format::NAMES[3],
+            Format::Csv => Format::NAMES[4],
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Format, String> {
+        match s {
+            "xml" => Ok(Format::Xml),
+            "text" | "txt" => Ok(Format::Text),
+            "markdown" | "md" => Ok(Format::Markdown),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("Unknown format '{}'", s)),
+        }
+    }
+}
+
+/// The prose in `content`, with Markdown stripped if `markdown` is set,
+/// and the author, date and source from any front matter.
+pub(super) fn prose(content: &str, markdown: bool) -> Data {
+    let (mut data, body) = front_matter(content);
+    data.text = normalize(&paragraphs(body, markdown));
+    data
+}
+
+/// Reads the author, date and source from YAML-style front matter between `---`
+/// lines, returning them with the rest of the content.
+fn front_matter(content: &str) -> (Data, &str) {
+    let mut data = Data::default();
+    let rest = match content.strip_prefix("---\n") {
+        Some(rest) => rest,
+        None => return (data, content),
+    };
+    let (matter, body) = match rest.find("\n---\n") {
+        Some(end) => (&rest[..end], &rest[end + 5..]),
+        None => match rest.strip_suffix("\n---") {
+            Some(matter) => (matter, ""),
+            None => return (data, content),
+        },
+    };
+    for line in matter.lines() {
+        let (key, value) = match line.split_once(':') {
+            Some((key, value)) => (key.trim(), value.trim().trim_matches(['"', '\''])),
+            None => continue,
+        };
+        match key {
+            "author" => data.author = Some(value.to_string()),
+            "date" => data.date = iso_date(value),
+            "source" => data.source = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    (data, body)
+}
+
+/// A `YYYY-MM-DD` date as `[d, m, y]`.
+pub(super) fn iso_date(s: &str) -> Option<[u16; 3]> {
+    let mut parts = s.splitn(3, '-').map(|part| part.parse::<u16>().ok());
+    let (y, m, d) = (parts.next()??, parts.next()??, parts.next()??);
+    Some([d, m, y])
+}
+
+/// The prose in `body` as one line per paragraph, with Markdown stripped
+/// if `markdown` is set.
+fn paragraphs(body: &str, markdown: bool) -> String {
+    let mut paragraphs = Vec::new();
+    let mut paragraph: Vec<String> = Vec::new();
+    let mut fence: Option<&str> = None;
+    for line in body.lines() {
+        let trimmed = line.trim();
+        if markdown {
+            if let Some(marker) = fence {
+                if trimmed.starts_with(marker) {
+                    fence = None;
+                }
+                continue;
+            }
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                fence = Some(&trimmed[..3]);
+                continue;
+            }
+        }
+        let line = match markdown {
+            true => markdown_line(trimmed),
+            false => Some(trimmed.to_string()),
+        };
+        match line {
+            Some(line) if !line.is_empty() => {
+                // headings and list items stand alone
+                if markdown && (trimmed.starts_with('#') || is_list_item(trimmed)) {
+                    paragraphs.push(std::mem::take(&mut paragraph).join(" "));
+                    paragraphs.push(line);
+                } else {
+                    paragraph.push(line);
+                }
+            }
+            _ => paragraphs.push(std::mem::take(&mut paragraph).join(" ")),
+        }
+    }
+    paragraphs.push(paragraph.join(" "));
+    paragraphs
+        .into_iter()
+        .map(|p| p.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|p| !p.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn is_list_item(line: &str) -> bool {
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    ["- ", "* ", "+ "]
+        .iter()
+        .any(|marker| line.starts_with(marker))
+        || (digits > 0 && line[digits..].starts_with(". "))
+}
+
+/// The prose in a line of Markdown, or `None` if it has none.
+fn markdown_line(line: &str) -> Option<String> {
+    // rules, link definitions, table rules and html comments
+    let is_rule = line.len() >= 3
+        && ["-", "*", "_"]
+            .iter()
+            .any(|c| line.chars().all(|l| l.to_string() == *c || l == ' '));
+    let is_table_rule = line.starts_with('|') && line.chars().all(|c| "|-: ".contains(c));
+    let is_definition = line.starts_with('[') && line.contains("]:");
+    if is_rule || is_table_rule || is_definition || line.starts_with("<!--") {
+        return None;
+    }
+    let mut line = line.trim_start_matches('>').trim_start();
+    line = line.trim_start_matches('#').trim_start();
+    if is_list_item(line) {
+        line = line.split_once(' ').map_or("", |(_, item)| item);
+    }
+    Some(inline(&line.replace('|', " ")))
+}
+
+/// Strips inline Markdown: emphasis, code spans, links, images and html.
+fn inline(s: &str) -> String {
+    let chars = s.chars().collect::<Vec<_>>();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let rest = chars[i..].iter().collect::<String>();
+        match c {
+            '!' if rest.starts_with("![") => {
+                // images are dropped, with their alt text
+                i += skip_link(&chars[i + 1..]).map_or(1, |(_, len)| len + 1);
+            }
+            '[' => match skip_link(&chars[i..]) {
+                Some((text, len)) => {
+                    out.push_str(&inline(&text));
+                    i += len;
+                }
+                None => {
+                    out.push(c);
+                    i += 1;
+                }
+            },
+            '<' if is_tag(&chars[i + 1..]) => match rest.find('>') {
+                Some(end) => i += rest[..end].chars().count() + 1,
+                None => {
+                    out.push(c);
+                    i += 1;
+                }
+            },
+            '`' => i += 1,
+            '*' => {
+                // asterisks between spaces are kept, as in 2 * 3
+                let spaced = |c: Option<&char>| c.is_none_or(|c| c.is_whitespace());
+                if spaced(i.checked_sub(1).map(|j| &chars[j])) && spaced(chars.get(i + 1)) {
+                    out.push(c);
+                }
+                i += 1;
+            }
+            '~' if rest.starts_with("~~") => i += 2,
+            '_' => {
+                // underscores inside words are kept, as in snake_case
+                let before = i > 0 && chars[i - 1].is_alphanumeric();
+                let after = chars.get(i + 1).is_some_and(|c| c.is_alphanumeric());
+                if before && after {
+                    out.push(c);
+                }
+                i += 1;
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// The text of a link starting at `chars`, `[text](url)` or `[text][ref]`,
+/// and how many characters the link takes.
+fn skip_link(chars: &[char]) -> Option<(String, usize)> {
+    let close = chars.iter().position(|&c| c == ']')?;
+    let text = chars[1..close].iter().collect::<String>();
+    let end = match chars.get(close + 1) {
+        Some('(') => ')',
+        Some('[') => ']',
+        _ => return Some((text, close + 1)),
+    };
+    let target_end = chars[close + 1..].iter().position(|&c| c == end)?;
+    Some((text, close + 1 + target_end + 1))
+}
+
+/// Whether the characters after a `<` look like an html tag or comment.
+fn is_tag(chars: &[char]) -> bool {
+    chars
+        .first()
+        .is_some_and(|&c| c.is_ascii_alphabetic() || c == '/' || c == '!')
+}
+
+/// Replaces typographic punctuation with its ASCII equivalent.
+pub(super) fn normalize(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\u{2018}' | '\u{2019}' | '\u{201a}' | '\u{2032}' => out.push('\''),
+            '\u{201c}' | '\u{201d}' | '\u{201e}' | '\u{2033}' | '\u{ab}' | '\u{bb}' => {
+                out.push('"')
+            }
+            '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2212}' => out.push('-'),
+            '\u{2013}' | '\u{2014}' | '\u{2015}' => out.push_str(" - "),
+            '\u{2026}' => out.push_str("..."),
+            '\u{a0}' | '\u{2002}' | '\u{2003}' | '\u{2009}' => out.push(' '),
+            '\u{200b}' | '\u{feff}' | '\u{ad}' => {}
+            c => out.push(c),
+        }
+    }
+    out.lines()
+        .map(|line| {
+            line.split(' ')
+                .filter(|w| !w.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
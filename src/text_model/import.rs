@@ -0,0 +1,256 @@
+use super::data::{unsupported_character, Data};
+use super::format::{iso_date, normalize, prose};
+use super::{DataError, Format, ParseError};
+use crate::difficulty::Bounds;
+use serde_json::Value;
+
+/// The names of the fields in imported records that hold each part of a
+/// text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fields {
+    pub text: String,
+    pub author: String,
+    pub source: String,
+    /// Read as a `YYYY-MM-DD` date.
+    pub date: String,
+}
+
+impl Default for Fields {
+    fn default() -> Fields {
+        Fields {
+            text: "text".to_string(),
+            author: "author".to_string(),
+            source: "source".to_string(),
+            date: "date".to_string(),
+        }
+    }
+}
+
+/// How to read a file of texts.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImportOptions {
+    /// The file's format, or `None` to guess it.
+    pub format: Option<Format>,
+    pub fields: Fields,
+    /// Leave out texts outside these difficulties.
+    pub difficulty: Bounds,
+}
+
+/// The texts read from a file, and the problems with the entries that
+/// were left out.
+#[derive(Debug, Default)]
+pub struct Imported {
+    pub texts: Vec<Data>,
+    pub problems: Vec<ParseError>,
+}
+
+/// Reads every text in `content`, normalizing and checking each as the
+/// XML parser would. Entries that cannot be typed are reported rather
+/// than stopping the import; a file that cannot be read at all is an
+/// error.
+pub fn import(content: &str, format: Format, fields: &Fields) -> Result<Imported, ParseError> {
+    let entries = match format {
+        Format::Xml => {
+            return Ok(Imported {
+                texts: Data::all_from_string(content)?,
+                problems: Vec::new(),
+            })
+        }
+        Format::Text => vec![(Ok(prose(content, false)), 0)],
+        Format::Markdown => vec![(Ok(prose(content, true)), 0)],
+        Format::Json => json_records(content, fields)?,
+        Format::Csv => csv_records(content, fields)?,
+    };
+    let mut imported = Imported::default();
+    for (entry, offset) in entries {
+        let (at, error) = match entry {
+            Ok(data) => match unsupported_character(&data.text) {
+                Some(c) => {
+                    let at = content[offset..].find(c).map_or(offset, |i| offset + i);
+                    (at, DataError::UnsupportedCharacter(c))
+                }
+                None if data.text.is_empty() => (offset, DataError::EmptyText),
+                None => {
+                    imported.texts.push(data);
+                    continue;
+                }
+            },
+            Err(error) => (offset, error),
+        };
+        imported.problems.push(ParseError::new(content, at, error));
+    }
+    Ok(imported)
+}
+
+/// An entry read from a collection, or why it could not be, with the
+/// offset it starts at.
+type Entry = (Result<Data, DataError>, usize);
+
+/// The records in a JSON array of objects, with their fields named by
+/// `fields`.
+fn json_records(content: &str, fields: &Fields) -> Result<Vec<Entry>, ParseError> {
+    let records = match serde_json::from_str::<Value>(content) {
+        Ok(Value::Array(records)) => records,
+        Ok(_) => {
+            let error =
+                <serde_json::Error as serde::de::Error>::custom("expected an array of records");
+            return Err(ParseError::new(content, 0, DataError::Json(error)));
+        }
+        Err(e) => {
+            let offset = offset_of(content, e.line(), e.column());
+            return Err(ParseError::new(content, offset, DataError::Json(e)));
+        }
+    };
+    let mut entries = Vec::new();
+    for (record, offset) in records.into_iter().zip(element_offsets(content)) {
+        let field = |name: &str| match record.get(name) {
+            Some(Value::String(s)) => Some(s.clone()),
+            Some(Value::Null) | None => None,
+            Some(value) => Some(value.to_string()),
+        };
+        let text = field(&fields.text);
+        let entry = match text {
+            Some(text) => Ok(Data {
+                text: normalize(&text.split_whitespace().collect::<Vec<_>>().join(" ")),
+                author: field(&fields.author),
+                date: field(&fields.date).as_deref().and_then(iso_date),
+                source: field(&fields.source),
+            }),
+            None => Err(DataError::MissingField(fields.text.clone())),
+        };
+        entries.push((entry, offset));
+    }
+    Ok(entries)
+}
+
+/// The rows of a CSV file, with their columns named by `fields` as they
+/// appear in the header row.
+fn csv_records(content: &str, fields: &Fields) -> Result<Vec<Entry>, ParseError> {
+    let mut rows = csv_rows(content)?.into_iter();
+    let header = match rows.next() {
+        Some((header, _)) => header,
+        None => return Ok(Vec::new()),
+    };
+    let column = |name: &str| header.iter().position(|h| h.trim() == name);
+    let text = column(&fields.text)
+        .ok_or_else(|| ParseError::new(content, 0, DataError::MissingField(fields.text.clone())))?;
+    let (author, date, source) = (
+        column(&fields.author),
+        column(&fields.date),
+        column(&fields.source),
+    );
+    let mut entries = Vec::new();
+    for (row, offset) in rows {
+        let field = |i: Option<usize>| {
+            i.and_then(|i| row.get(i))
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let entry = match row.get(text) {
+            Some(text) => Ok(Data {
+                text: normalize(&text.split_whitespace().collect::<Vec<_>>().join(" ")),
+                author: field(author),
+                date: field(date).as_deref().and_then(iso_date),
+                source: field(source),
+            }),
+            None => Err(DataError::MissingField(fields.text.clone())),
+        };
+        entries.push((entry, offset));
+    }
+    Ok(entries)
+}
+
+/// The rows in `content`, each with the offset it starts at. Fields may
+/// be quoted, with `""` for a quote, to hold commas and line breaks.
+/// Blank lines are skipped.
+fn csv_rows(content: &str) -> Result<Vec<(Vec<String>, usize)>, ParseError> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut row_start = 0;
+    let mut chars = content.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' if field.is_empty() => loop {
+                match chars.next() {
+                    Some((_, '"')) if chars.peek().map(|&(_, c)| c) == Some('"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    Some((_, '"')) => match chars.peek() {
+                        Some((_, ',' | '\r' | '\n')) | None => break,
+                        Some(&(at, _)) => {
+                            let error = DataError::Csv("text after a closing quote");
+                            return Err(ParseError::new(content, at, error));
+                        }
+                    },
+                    Some((_, c)) => field.push(c),
+                    None => {
+                        let error = DataError::Csv("unclosed quote");
+                        return Err(ParseError::new(content, i, error));
+                    }
+                }
+            },
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' if chars.peek().map(|&(_, c)| c) == Some('\n') => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                if row.iter().any(|field| !field.is_empty()) {
+                    rows.push((std::mem::take(&mut row), row_start));
+                }
+                row.clear();
+                row_start = i + 1;
+            }
+            c => field.push(c),
+        }
+    }
+    row.push(field);
+    if row.iter().any(|field| !field.is_empty()) {
+        rows.push((row, row_start));
+    }
+    Ok(rows)
+}
+
+/// Where each element of the JSON array in `content` starts, as parsed
+/// values carry no positions.
+fn element_offsets(content: &str) -> Vec<usize> {
+    let mut offsets = Vec::new();
+    let (mut depth, mut in_string, mut escaped) = (0, false, false);
+    // the last character outside strings that was not whitespace
+    let mut previous = ' ';
+    for (i, c) in content.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        if c.is_whitespace() {
+            continue;
+        }
+        if depth == 1 && matches!(previous, ',' | '[') && !matches!(c, ',' | ']') {
+            offsets.push(i);
+        }
+        match c {
+            '"' => in_string = true,
+            '[' | '{' => depth += 1,
+            ']' | '}' => depth -= 1,
+            _ => {}
+        }
+        previous = c;
+    }
+    offsets
+}
+
+/// The offset of a 1-based line and column in `content`.
+fn offset_of(content: &str, line: usize, column: usize) -> usize {
+    let line_start = content
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum::<usize>();
+    (line_start + column.saturating_sub(1)).min(content.len())
+}
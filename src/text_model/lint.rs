@@ -0,0 +1,202 @@
+use super::{DataError, ParseError};
+use quick_xml::{events::Event, Reader};
+
+/// Every problem with the klata text file content in `source`, rather than
+/// just the first. Malformed XML stops the search, as nothing after it can
+/// be trusted.
+pub fn lint(source: &str) -> Vec<ParseError> {
+    let mut problems = Vec::new();
+    let mut reader = Reader::from_str(source);
+    let mut elements: Vec<String> = Vec::new();
+    let mut text = None;
+    // where the current text starts, and how many have been checked
+    let mut texts = (0, 0);
+    let mut date = ([0u16; 3], 0);
+    let mut buffer = Vec::new();
+    loop {
+        buffer.clear();
+        let offset = reader.buffer_position();
+        let event = match reader.read_event(&mut buffer) {
+            Ok(event) => event,
+            Err(e) => {
+                let at = reader.buffer_position();
+                problems.push(ParseError::new(source, at, e.into()));
+                return problems;
+            }
+        };
+        match event {
+            Event::Start(ref e) | Event::Empty(ref e) => {
+                let name = String::from_utf8_lossy(e.name()).into_owned();
+                let known: &[&str] = match elements.last().map(String::as_str) {
+                    None => &["klata_text", "klata_library"],
+                    Some("klata_library") => &["klata_text"],
+                    Some("klata_text") => &["text", "author", "date", "source"],
+                    Some("date") => &["d", "m", "y"],
+                    Some(_) => &[],
+                };
+                if !known.contains(&name.as_str()) {
+                    let error = DataError::UnknownElement(name.clone());
+                    problems.push(ParseError::new(source, offset, error));
+                }
+                if name == "date" {
+                    date = ([0; 3], offset);
+                }
+                if name == "klata_text" {
+                    texts.0 = offset;
+                }
+                if matches!(event, Event::Start(_)) {
+                    elements.push(name);
+                }
+            }
+            Event::End(_) => {
+                let closed = elements.pop();
+                let error = check_date(date.0).filter(|_| closed.as_deref() == Some("date"));
+                if let Some(error) = error {
+                    problems.push(ParseError::new(source, date.1, error));
+                }
+                if closed.as_deref() == Some("klata_text") {
+                    check_text(source, text.take(), texts.0, &mut problems);
+                    texts.1 += 1;
+                }
+            }
+            Event::Text(t) => {
+                let raw = String::from_utf8_lossy(t.escaped()).into_owned();
+                match elements.last().map(String::as_str) {
+                    Some("text") => text = Some((offset, raw)),
+                    Some(part @ ("d" | "m" | "y")) => match raw.trim().parse::<u16>() {
+                        Ok(value) => {
+                            date.0[["d", "m", "y"].iter().position(|p| p == &part).unwrap()] = value
+                        }
+                        Err(e) => problems.push(ParseError::new(source, offset, e.into())),
+                    },
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    if texts.1 == 0 {
+        check_text(source, text, 0, &mut problems);
+    }
+    problems.sort_by_key(|p| (p.line(), p.column()));
+    problems
+}
+
+/// Checks a text, as its raw content and where that starts, or reports
+/// it empty at `missing_at` if it has no content.
+fn check_text(
+    source: &str,
+    text: Option<(usize, String)>,
+    missing_at: usize,
+    problems: &mut Vec<ParseError>,
+) {
+    match text {
+        Some((offset, raw)) if !raw.trim().is_empty() => lint_text(source, offset, &raw, problems),
+        Some((offset, _)) => problems.push(ParseError::new(source, offset, DataError::EmptyText)),
+        None => problems.push(ParseError::new(source, missing_at, DataError::EmptyText)),
+    }
+}
+
+/// Checks the content of `<text>`, as written at `offset` in `source`.
+fn lint_text(source: &str, offset: usize, raw: &str, problems: &mut Vec<ParseError>) {
+    let mut push = |at: usize, error| problems.push(ParseError::new(source, offset + at, error));
+    for (i, c) in raw.char_indices() {
+        if c == '&' {
+            // entities are checked once unescaped, below
+            let entity = &raw[i..raw[i..].find(';').map_or(raw.len(), |end| i + end + 1)];
+            match quick_xml::escape::unescape(entity.as_bytes()) {
+                Ok(unescaped) => {
+                    if let Some(c) = String::from_utf8_lossy(&unescaped)
+                        .chars()
+                        .find(|&c| !supported(c))
+                    {
+                        push(i, DataError::UnsupportedCharacter(c));
+                    }
+                }
+                Err(e) => push(i, DataError::Xml(quick_xml::Error::EscapeError(e))),
+            }
+        } else if !supported(c) {
+            push(i, DataError::UnsupportedCharacter(c));
+        }
+    }
+    // leading whitespace is trimmed from the text, so is never typed
+    let body_start = raw.len() - raw.trim_start().len();
+    let body = &raw[body_start..];
+    for (start, line) in lines(body) {
+        let start = body_start + start;
+        if line.contains('\t') {
+            push(
+                start + line.find('\t').unwrap(),
+                DataError::SuspiciousWhitespace("tab"),
+            );
+        }
+        if line.ends_with('\r') {
+            push(
+                start + line.len() - 1,
+                DataError::SuspiciousWhitespace("carriage return"),
+            );
+        }
+        if let Some(at) = line.find("  ").filter(|&at| !line[..at].trim().is_empty()) {
+            push(
+                start + at,
+                DataError::SuspiciousWhitespace("repeated spaces"),
+            );
+        }
+        let content = line.trim_end_matches('\r');
+        let trimmed = content.trim_end();
+        if !trimmed.is_empty()
+            && trimmed.len() < content.len()
+            && start + content.len() < raw.trim_end().len()
+        {
+            push(
+                start + trimmed.len(),
+                DataError::SuspiciousWhitespace("trailing whitespace on a line"),
+            );
+        }
+        if start > body_start && !trimmed.is_empty() && trimmed.starts_with(char::is_whitespace) {
+            push(
+                start,
+                DataError::SuspiciousWhitespace("indented line, which must be typed"),
+            );
+        }
+    }
+    let end = raw.trim_end().len();
+    if end < raw.len() {
+        push(
+            end,
+            DataError::SuspiciousWhitespace(
+                "whitespace at the end of the text, which must be typed",
+            ),
+        );
+    }
+}
+
+/// The lines of `s`, each with the offset it starts at.
+fn lines(s: &str) -> impl Iterator<Item = (usize, &str)> {
+    s.split('\n').scan(0, |start, line| {
+        let item = (*start, line);
+        *start += line.len() + 1;
+        Some(item)
+    })
+}
+
+/// Whether the parser accepts `c` in a text.
+fn supported(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c.is_ascii_whitespace() || c.is_ascii_punctuation()
+}
+
+fn check_date([d, m, y]: [u16; 3]) -> Option<DataError> {
+    let leap = (y % 4 == 0 && y % 100 != 0) || y % 400 == 0;
+    let days = match m {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => 0,
+    };
+    if d == 0 || d > days {
+        return Some(DataError::ImpossibleDate([d, m, y]));
+    }
+    None
+}
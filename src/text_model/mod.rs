@@ -1,9 +1,23 @@
 #[cfg(test)]
 mod tests;
 
+mod correction;
 mod data;
+mod format;
+mod import;
+mod lint;
+mod parse_error;
+#[allow(clippy::module_inception)]
 mod text_model;
+mod validate;
 
-pub use text_model::TextModel;
+pub use correction::Correction;
+pub use data::{library_to_xml, unsupported_character, Data, DataError};
+pub use format::Format;
+pub use import::{import, Fields, ImportOptions, Imported};
+pub use lint::lint;
+pub use validate::{validate, Problem};
+pub use parse_error::ParseError;
+pub use text_model::{text_id, TextModel};
 pub use text_model::Character;
 pub use text_model::CharacterStatus;
\ No newline at end of file
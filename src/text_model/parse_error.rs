@@ -0,0 +1,81 @@
+use super::DataError;
+use std::{
+    error::Error,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// A problem with a text file, and where in the file it is.
+#[derive(Debug)]
+pub struct ParseError {
+    path: Option<PathBuf>,
+    line: usize,
+    column: usize,
+    snippet: String,
+    error: DataError,
+}
+
+impl ParseError {
+    /// Locates `error` at byte `offset` in `source`.
+    pub fn new(source: &str, offset: usize, error: DataError) -> ParseError {
+        let mut offset = offset.min(source.len());
+        while !source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[offset..]
+            .find('\n')
+            .map_or(source.len(), |i| offset + i);
+        ParseError {
+            path: None,
+            line: source[..offset].matches('\n').count() + 1,
+            column: source[line_start..offset].chars().count() + 1,
+            snippet: source[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+            error,
+        }
+    }
+
+    /// Names the file the error is in.
+    pub fn in_file<P: AsRef<Path>>(mut self, path: P) -> ParseError {
+        self.path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn error(&self) -> &DataError {
+        &self.error
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(path) = &self.path {
+            write!(f, "{}:", path.display())?;
+        }
+        writeln!(f, "{}:{}: {}", self.line, self.column, self.error)?;
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.snippet)?;
+        write!(f, "{} | {}^", gutter, " ".repeat(self.column - 1))
+    }
+}
+
+impl Error for ParseError {}
+
+/// The Unicode name of `c`, if it has one.
+pub fn char_name(c: char) -> Option<String> {
+    unicode_names2::name(c).map(|name| name.to_string())
+}
@@ -68,14 +68,72 @@ fn text_with_line_breaks_is_valid() {
 fn text_with_directed_speech_marks_not_valid() {
     let file_str = "<klata_text><text>\u{201c}</text></klata_text>";
     let data = data::Data::from_string(file_str);
-    assert_eq!(&format!("{}",data.unwrap_err()), "Unsupported character '\u{201c}'")
+    assert_eq!(
+        &format!("{}", data.unwrap_err().error()),
+        "Unsupported character '\u{201c}' (U+201C LEFT DOUBLE QUOTATION MARK)"
+    )
 }
 
 #[test]
 fn text_with_directed_apostrophes_not_valid() {
     let file_str = "<klata_text><text>Ben\u{2019}s</text></klata_text>";
     let data = data::Data::from_string(file_str);
-    assert_eq!(&format!("{}",data.unwrap_err()), "Unsupported character '\u{2019}'")
+    assert_eq!(
+        &format!("{}", data.unwrap_err().error()),
+        "Unsupported character '\u{2019}' (U+2019 RIGHT SINGLE QUOTATION MARK)"
+    )
+}
+
+#[test]
+fn unnamed_unsupported_character_shows_code_point() {
+    let file_str = "<klata_text><text>\u{e000}</text></klata_text>";
+    let data = data::Data::from_string(file_str);
+    assert_eq!(
+        &format!("{}", data.unwrap_err().error()),
+        "Unsupported character '\u{e000}' (U+E000)"
+    )
+}
+
+#[test]
+fn unsupported_characters_are_named_from_the_unicode_database() {
+    let file_str = "<klata_text><text>\u{263a}</text></klata_text>";
+    let data = data::Data::from_string(file_str);
+    assert_eq!(
+        &format!("{}", data.unwrap_err().error()),
+        "Unsupported character '\u{263a}' (U+263A WHITE SMILING FACE)"
+    )
+}
+
+#[test]
+fn unsupported_character_error_points_at_it() {
+    let file_str = "<klata_text>\n  <text>Ben\u{2019}s</text>\n</klata_text>";
+    let error = data::Data::from_string(file_str).unwrap_err();
+    assert_eq!(
+        format!("{}", error),
+        [
+            "2:12: Unsupported character '\u{2019}' (U+2019 RIGHT SINGLE QUOTATION MARK)",
+            "  |",
+            "2 |   <text>Ben\u{2019}s</text>",
+            "  |            ^",
+        ]
+        .join("\n")
+    )
+}
+
+#[test]
+fn unsupported_character_as_entity_points_at_text() {
+    let file_str = "<klata_text><text>Ben&#8217;s</text></klata_text>";
+    let error = data::Data::from_string(file_str).unwrap_err();
+    assert!(format!("{}", error).starts_with("1:19: "));
+}
+
+#[test]
+fn malformed_xml_error_has_line_and_column() {
+    let file_str = "<klata_text>\n<text>abc</txt>\n</klata_text>";
+    let error = data::Data::from_string(file_str).unwrap_err();
+    assert!(matches!(error.error(), DataError::Xml(_)));
+    assert!(format!("{}", error).starts_with("2:"));
+    assert!(format!("{}", error).contains("2 | <text>abc</txt>"));
 }
 
 #[test]
@@ -83,4 +141,359 @@ fn leading_whitespace_trimmed() {
     let file_str = "<klata_text><text> Dredd</text></klata_text>";
     let data = data::Data::from_string(file_str).unwrap();
     assert_eq!(data.text, "Dredd".to_string());
-}
\ No newline at end of file
+}
+
+fn make_text_model(correction: Correction) -> TextModel {
+    let mut text = TextModel::from_string("<klata_text><text>ab cd</text></klata_text>").unwrap();
+    text.set_correction(correction);
+    text
+}
+
+fn type_str(text: &mut TextModel, s: &str) {
+    s.chars().for_each(|c| text.type_character(c));
+}
+
+#[test]
+fn free_correction_erases_to_start() {
+    let mut text = make_text_model(Correction::Free);
+    type_str(&mut text, "ab c");
+    (0..5).for_each(|_| text.backspace());
+    assert_eq!(text.cursor(), 0);
+}
+
+#[test]
+fn disabled_correction_never_erases() {
+    let mut text = make_text_model(Correction::Disabled);
+    type_str(&mut text, "ax");
+    text.backspace();
+    assert_eq!(text.cursor(), 2);
+}
+
+#[test]
+fn word_correction_erases_within_word() {
+    let mut text = make_text_model(Correction::Word);
+    type_str(&mut text, "ab cx");
+    text.backspace();
+    text.backspace();
+    text.backspace();
+    assert_eq!(text.cursor(), 3);
+}
+
+#[test]
+fn word_correction_cannot_erase_wrong_previous_word() {
+    let mut text = make_text_model(Correction::Word);
+    type_str(&mut text, "ax ");
+    text.backspace();
+    assert_eq!(text.cursor(), 3);
+}
+
+#[test]
+fn confidence_correction_cannot_erase_correct_previous_word() {
+    let mut text = make_text_model(Correction::Confidence);
+    type_str(&mut text, "ab c");
+    text.backspace();
+    text.backspace();
+    assert_eq!(text.cursor(), 3);
+}
+
+#[test]
+fn confidence_correction_erases_wrong_previous_word() {
+    let mut text = make_text_model(Correction::Confidence);
+    type_str(&mut text, "xb ");
+    text.backspace();
+    text.backspace();
+    assert_eq!(text.cursor(), 1);
+}
+
+#[test]
+fn correction_policies_round_trip_through_names() {
+    for name in Correction::NAMES {
+        assert_eq!(name.parse::<Correction>().unwrap().to_string(), name);
+    }
+}
+
+fn kinds(source: &str) -> Vec<&'static str> {
+    lint(source).iter().map(|p| p.error().kind()).collect()
+}
+
+#[test]
+fn clean_text_has_no_lint() {
+    let file_str = "<klata_text>\n  <text>\n    Hello\nWorld</text>\n  <author>A</author>\n  <date><d>29</d><m>2</m><y>2000</y></date>\n</klata_text>";
+    assert_eq!(kinds(file_str), Vec::<&str>::new());
+}
+
+#[test]
+fn lint_reports_every_unsupported_character() {
+    let file_str = "<klata_text><text>\u{201c}Hi\u{201d} &#8230;</text></klata_text>";
+    assert_eq!(kinds(file_str), ["unsupported-character"; 3]);
+}
+
+#[test]
+fn lint_reports_empty_text() {
+    assert_eq!(kinds("<klata_text><text> </text></klata_text>"), ["empty-text"]);
+    assert_eq!(kinds("<klata_text></klata_text>"), ["empty-text"]);
+}
+
+#[test]
+fn lint_reports_impossible_dates() {
+    let file_str = "<klata_text><text>a</text><date><d>29</d><m>2</m><y>1900</y></date></klata_text>";
+    assert_eq!(kinds(file_str), ["impossible-date"]);
+    let file_str = "<klata_text><text>a</text><date><d>1</d><m>13</m><y>1900</y></date></klata_text>";
+    assert_eq!(kinds(file_str), ["impossible-date"]);
+}
+
+#[test]
+fn lint_reports_unknown_elements() {
+    let file_str = "<klata_text><text>a</text><title>b</title><date><w>1</w></date></klata_text>";
+    let problems = lint(file_str);
+    let messages = problems.iter().map(|p| p.error().to_string()).collect::<Vec<_>>();
+    assert!(messages.contains(&"Unknown element <title>".to_string()));
+    assert!(messages.contains(&"Unknown element <w>".to_string()));
+}
+
+#[test]
+fn lint_reports_suspicious_whitespace() {
+    let file_str = "<klata_text><text>a  b\tc \n  d\n</text></klata_text>";
+    let problems = lint(file_str);
+    let messages = problems.iter().map(|p| p.error().to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        [
+            "Suspicious whitespace: repeated spaces",
+            "Suspicious whitespace: tab",
+            "Suspicious whitespace: trailing whitespace on a line",
+            "Suspicious whitespace: indented line, which must be typed",
+            "Suspicious whitespace: whitespace at the end of the text, which must be typed",
+        ]
+    );
+}
+
+#[test]
+fn lint_stops_at_malformed_xml() {
+    let file_str = "<klata_text><text>\u{201c}</txt></klata_text>";
+    assert_eq!(kinds(file_str), ["xml"]);
+}
+
+#[test]
+fn validate_searches_directories_and_finds_duplicates() {
+    let dir = std::env::temp_dir().join(format!("klata-validate-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("more")).unwrap();
+    let text = "<klata_text><text>same</text></klata_text>";
+    std::fs::write(dir.join("a.xml"), text).unwrap();
+    std::fs::write(dir.join("more").join("b.xml"), text).unwrap();
+    std::fs::write(dir.join("notes.txt"), "not a text").unwrap();
+
+    let problems = validate(&[&dir]).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let problems = problems.iter().map(Problem::from).collect::<Vec<_>>();
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].kind, "duplicate-text");
+    assert_eq!(problems[0].path, dir.join("more").join("b.xml"));
+    let first = dir.join("a.xml");
+    assert_eq!(problems[0].message, format!("The same text as {}:1", first.display()));
+}
+
+#[test]
+fn duplicates_in_a_library_point_at_both_texts() {
+    let library = "<klata_library>\n\
+        <klata_text><text>one</text></klata_text>\n\
+        <klata_text><text>two</text></klata_text>\n  \
+        <klata_text><text>one</text></klata_text>\n\
+        </klata_library>";
+    let path = std::env::temp_dir().join(format!("klata-library-dup-{}.xml", std::process::id()));
+    std::fs::write(&path, library).unwrap();
+    let problems = validate(&[&path]).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let problems = problems.iter().map(Problem::from).collect::<Vec<_>>();
+    assert_eq!(problems.len(), 1);
+    assert_eq!((problems[0].line, problems[0].column), (4, 3));
+    assert_eq!(problems[0].message, format!("The same text as {}:2", path.display()));
+}
+
+#[test]
+fn lint_reports_unknown_entities() {
+    let file_str = "<klata_text><text>fish &foo; chips</text></klata_text>";
+    assert!(data::Data::from_string(file_str).is_err());
+    assert_eq!(kinds(file_str), ["xml"]);
+}
+
+#[test]
+fn validate_reports_unreadable_files_and_carries_on() {
+    let dir = std::env::temp_dir().join(format!("klata-unreadable-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("a.xml"), b"<klata_text><text>\xff</text></klata_text>").unwrap();
+    std::fs::write(dir.join("b.xml"), "<klata_text><text></text></klata_text>").unwrap();
+
+    let problems = validate(&[&dir]).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let problems = problems.iter().map(Problem::from).collect::<Vec<_>>();
+    let kinds = problems.iter().map(|p| p.kind).collect::<Vec<_>>();
+    assert_eq!(kinds, ["unreadable", "empty-text"]);
+    assert_eq!(problems[0].path, dir.join("a.xml"));
+}
+
+fn converted(content: &str, format: Format) -> Data {
+    let mut imported = import(content, format, &Fields::default()).unwrap();
+    assert!(imported.problems.is_empty());
+    assert_eq!(imported.texts.len(), 1);
+    imported.texts.remove(0)
+}
+
+fn problem(content: &str, format: Format) -> ParseError {
+    let mut imported = import(content, format, &Fields::default()).unwrap();
+    assert!(imported.texts.is_empty());
+    imported.problems.remove(0)
+}
+
+#[test]
+fn format_is_detected_from_extension_then_content() {
+    assert_eq!(Format::detect("a.md", "<klata_text>"), Format::Markdown);
+    assert_eq!(Format::detect("a.TXT", ""), Format::Text);
+    assert_eq!(Format::detect("a.xml", ""), Format::Xml);
+    assert_eq!(Format::detect("-", "<klata_text><text>a</text></klata_text>"), Format::Xml);
+    assert_eq!(Format::detect("-", "a fortune"), Format::Text);
+    assert_eq!("md".parse::<Format>(), Ok(Format::Markdown));
+}
+
+#[test]
+fn plain_text_is_reflowed_into_paragraphs() {
+    let content = "  The first\nparagraph.\n\n\nThe \u{201c}second\u{201d}\u{2026} <b> & c\n";
+    let data = converted(content, Format::Text);
+    assert_eq!(data.text, "The first paragraph.\nThe \"second\"... <b> & c");
+    assert_eq!(data.author, None);
+}
+
+#[test]
+fn markdown_is_stripped_to_prose() {
+    let content = "\
+---
+title: Notes
+author: \"Ada Lovelace\"
+date: 1843-09-05
+---
+# A *heading*
+
+Some **bold** and `code`, a [link](http://a.b) and
+![an image](i.png) a snake_case word.
+
+```
+fn skipped() {}
+```
+
+- one
+- two
+
+> quoted <!-- hidden -->
+---
+";
+    let data = converted(content, Format::Markdown);
+    assert_eq!(
+        data.text,
+        "A heading\nSome bold and code, a link and a snake_case word.\none\ntwo\nquoted"
+    );
+    assert_eq!(data.author.as_deref(), Some("Ada Lovelace"));
+    assert_eq!(data.date, Some([5, 9, 1843]));
+}
+
+#[test]
+fn converted_text_is_checked_for_unsupported_characters() {
+    let error = problem("Caf\u{e9}\n", Format::Text);
+    assert_eq!(error.error().kind(), "unsupported-character");
+    assert_eq!((error.line(), error.column()), (1, 4));
+    let error = problem("---\nauthor: x\n---\n\n", Format::Markdown);
+    assert_eq!(error.error().kind(), "empty-text");
+}
+
+#[test]
+fn converted_text_round_trips_through_xml() {
+    let data = Data {
+        text: "Fish & <chips>\nsaid \"he\"".to_string(),
+        author: Some("A & B".to_string()),
+        date: Some([5, 9, 1843]),
+        source: Some("Notes".to_string()),
+    };
+    assert_eq!(Data::from_string(&data.to_xml()).unwrap(), data);
+    assert_eq!(Data::all_from_string(&library_to_xml(&[data])).unwrap().len(), 1);
+}
+
+#[test]
+fn xml_errors_stop_the_import() {
+    let result = import("<klata_text><text>a</txt>", Format::Xml, &Fields::default());
+    assert_eq!(result.unwrap_err().error().kind(), "xml");
+}
+
+#[test]
+fn json_records_are_imported_with_mapped_fields() {
+    let content = r#"[
+  {"quote": "Be  yourself.", "by": "Oscar Wilde", "year": "1890-01-01"},
+  {"quote": "Caf\u00e9 \u00e9", "by": "x"},
+  {"by": "nobody"},
+  {"quote": "", "by": "y"}
+]"#;
+    let fields = Fields {
+        text: "quote".to_string(),
+        author: "by".to_string(),
+        date: "year".to_string(),
+        ..Fields::default()
+    };
+    let imported = import(content, Format::Json, &fields).unwrap();
+    assert_eq!(imported.texts.len(), 1);
+    assert_eq!(imported.texts[0].text, "Be yourself.");
+    assert_eq!(imported.texts[0].author.as_deref(), Some("Oscar Wilde"));
+    assert_eq!(imported.texts[0].date, Some([1, 1, 1890]));
+    let problems = imported
+        .problems
+        .iter()
+        .map(|p| (p.line(), p.error().kind()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        problems,
+        [(3, "unsupported-character"), (4, "missing-field"), (5, "empty-text")]
+    );
+}
+
+#[test]
+fn malformed_json_is_located() {
+    let error = import("[\n  {\"text\": }\n]", Format::Json, &Fields::default()).unwrap_err();
+    assert_eq!(error.error().kind(), "json");
+    assert_eq!((error.line(), error.column()), (2, 12));
+}
+
+#[test]
+fn csv_rows_are_imported_with_quoted_fields() {
+    let content = "author,text,source\r\n\
+Ada,\"Hello, \"\"world\"\"\",Notes\r\n\
+\r\n\
+Bob,\"Two\nlines\",\r\n\
+Cy,Caf\u{e9},\r\n";
+    let imported = import(content, Format::Csv, &Fields::default()).unwrap();
+    let texts = imported.texts.iter().map(|d| d.text.as_str()).collect::<Vec<_>>();
+    assert_eq!(texts, ["Hello, \"world\"", "Two lines"]);
+    assert_eq!(imported.texts[0].source.as_deref(), Some("Notes"));
+    assert_eq!(imported.texts[1].source, None);
+    assert_eq!(imported.problems.len(), 1);
+    assert_eq!((imported.problems[0].line(), imported.problems[0].column()), (6, 7));
+}
+
+#[test]
+fn csv_needs_the_text_column_and_closed_quotes() {
+    let missing = import("quote\nhi\n", Format::Csv, &Fields::default()).unwrap_err();
+    assert_eq!(missing.error().to_string(), "No 'text' field");
+    let unclosed = import("text\n\"hi\n", Format::Csv, &Fields::default()).unwrap_err();
+    assert_eq!(unclosed.error().kind(), "csv");
+    assert_eq!(unclosed.line(), 2);
+}
+
+#[test]
+fn libraries_are_linted_text_by_text() {
+    let file_str = "<klata_library>\
+<klata_text><text>a</text><source>s</source></klata_text>\
+<klata_text><text></text></klata_text>\
+</klata_library>";
+    assert_eq!(kinds(file_str), ["empty-text"]);
+}
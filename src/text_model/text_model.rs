@@ -1,9 +1,8 @@
-use std::{
-    boxed::Box,
-    error::Error,
-};
+use super::{Correction, ParseError};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum CharacterStatus {
     Untyped,
     Correct,
@@ -30,6 +29,10 @@ impl Character {
         self.value
     }
 
+    pub fn wrong_attempts(&self) -> u32 {
+        self.wrong_attempts
+    }
+
     pub fn status(&self) -> CharacterStatus {
         if let Some(typed) = self.typed_value {
             match self.wrong_attempts {
@@ -63,24 +66,29 @@ impl Character {
 pub struct TextModel {
     buffer: Vec<Character>,
     cursor: usize,
-    _author: Option<String>,
+    correction: Correction,
+    author: Option<String>,
     _date: Option<[u16;3]>,
 }
 
 impl TextModel {
-    pub fn from_string(s: &str) -> Result<TextModel, Box<dyn Error>> {
-        let data = super::data::Data::from_string(s)?;
-        let buffer = data.text.chars().map(|c| Character::new(c)).collect::<Vec<_>>();
+    pub fn from_string(s: &str) -> Result<TextModel, ParseError> {
+        Ok(TextModel::from_data(super::data::Data::from_string(s)?))
+    }
+
+    pub(super) fn from_data(data: super::data::Data) -> TextModel {
+        let buffer = data.text.chars().map(Character::new).collect::<Vec<_>>();
         let cursor = 0;
-        Ok(TextModel { 
+        TextModel { 
             buffer, 
             cursor, 
-            _author: data.author, 
+            correction: Correction::default(),
+            author: data.author, 
             _date: data.date,
-        })
+        }
     }
 
-    pub fn characters(&self) -> std::slice::Iter<Character> {
+    pub fn characters(&self) -> std::slice::Iter<'_, Character> {
         self.buffer.iter()
     }
 
@@ -88,19 +96,85 @@ impl TextModel {
         self.cursor
     }
 
+    pub fn correction(&self) -> Correction {
+        self.correction
+    }
+
+    pub fn set_correction(&mut self, correction: Correction) {
+        self.correction = correction;
+    }
+
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    /// A stable identifier for the text, hashed from its characters.
+    pub fn id(&self) -> String {
+        text_id(&self.text())
+    }
+
+    /// The characters of the text, as typed when it is right.
+    pub fn text(&self) -> String {
+        self.buffer.iter().map(Character::value).collect()
+    }
+
+    /// Adds `text` to the end, for texts that go on as they are typed.
+    pub fn extend(&mut self, text: &str) {
+        self.buffer.extend(text.chars().map(Character::new));
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.cursor >= self.buffer.len()
+    }
+
     pub fn type_character(&mut self, c: char) {
         if self.buffer.len() <= self.cursor {
             return;
         }
         self.buffer[self.cursor].attempt(c);
-        self.cursor = self.cursor + 1;
+        self.cursor += 1;
     }
 
     pub fn backspace(&mut self) {
-        if self.cursor == 0 {
+        if !self.can_backspace() {
             return;
         }
         self.cursor -= 1;
         self.buffer[self.cursor].erase();
     }
-}
\ No newline at end of file
+
+    fn can_backspace(&self) -> bool {
+        if self.cursor == 0 {
+            return false;
+        }
+        let previous = &self.buffer[self.cursor - 1];
+        match self.correction {
+            Correction::Free => true,
+            Correction::Disabled => false,
+            Correction::Word => !previous.value().is_whitespace(),
+            Correction::Confidence => {
+                // the previous character ends a word, so check the whole word
+                if !previous.value().is_whitespace() {
+                    return true;
+                }
+                self.buffer[..self.cursor]
+                    .iter()
+                    .rev()
+                    .skip(1)
+                    .take_while(|c| !c.value().is_whitespace())
+                    .chain(std::iter::once(previous))
+                    .any(|c| c.status() == CharacterStatus::Wrong)
+            }
+        }
+    }
+}
+
+/// The identifier of a text with the characters `text`, as `TextModel::id`.
+pub fn text_id(text: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in text.bytes() {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
@@ -0,0 +1,94 @@
+use super::data::Data;
+use super::{lint, DataError, ParseError, TextModel};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+/// A problem found by `validate`, as reported in machine-readable output.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Problem {
+    pub path: PathBuf,
+    pub line: usize,
+    pub column: usize,
+    pub kind: &'static str,
+    pub message: String,
+}
+
+impl From<&ParseError> for Problem {
+    fn from(e: &ParseError) -> Problem {
+        Problem {
+            path: e.path().map(Path::to_path_buf).unwrap_or_default(),
+            line: e.line(),
+            column: e.column(),
+            kind: e.error().kind(),
+            message: e.error().to_string(),
+        }
+    }
+}
+
+/// Lints every text file in `paths`, looking through directories for
+/// `.xml` files, and reports texts which appear more than once. Files that
+/// can't be read are reported as problems too.
+pub fn validate<P: AsRef<Path>>(paths: &[P]) -> io::Result<Vec<ParseError>> {
+    let mut files = Vec::new();
+    for path in paths {
+        find_texts(path.as_ref(), &mut files)?;
+    }
+    let mut problems = Vec::new();
+    // where each text was first found, by file and line
+    let mut ids: HashMap<String, (PathBuf, usize)> = HashMap::new();
+    for path in files {
+        let source = match std::fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(e) => {
+                let error = ParseError::new("", 0, DataError::Unreadable(e));
+                problems.push(error.in_file(&path));
+                continue;
+            }
+        };
+        let mut found = lint(&source);
+        // the parser has the last word on whether the file loads
+        let texts = Data::located_from_string(&source).unwrap_or_else(|e| {
+            let kind = e.error().kind();
+            if !found.iter().any(|p| p.line() == e.line() && p.error().kind() == kind) {
+                found.push(e);
+            }
+            Vec::new()
+        });
+        problems.extend(found.into_iter().map(|e| e.in_file(&path)));
+        // a library may repeat its own texts, as well as other files'
+        for (data, start) in texts {
+            let id = TextModel::from_data(data).id();
+            if let Some((first, line)) = ids.get(&id) {
+                let error = DataError::DuplicateText(first.clone(), *line);
+                problems.push(ParseError::new(&source, start, error).in_file(&path));
+            } else {
+                let line = source[..start].matches('\n').count() + 1;
+                ids.insert(id, (path.clone(), line));
+            }
+        }
+    }
+    Ok(problems)
+}
+
+/// Adds `path` to `files`, or the `.xml` files under it if it is a
+/// directory, in a stable order.
+fn find_texts(path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+    let mut entries = std::fs::read_dir(path)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() || entry.extension().is_some_and(|e| e == "xml") {
+            find_texts(&entry, files)?;
+        }
+    }
+    Ok(())
+}
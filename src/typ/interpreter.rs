@@ -0,0 +1,185 @@
+use super::{Command, Drill, Menu, Program};
+use std::collections::HashSet;
+
+/// The errors gtypist allows in an exercise, as a percentage, until a
+/// script says otherwise.
+const DEFAULT_ERROR_MAX: f64 = 3.0;
+
+/// Runs a lesson script, stopping at each command that needs the typist.
+#[derive(Debug, Default)]
+pub struct Interpreter {
+    program: Program,
+    /// The index of the next command to run.
+    next: usize,
+    /// The index of the last command that stopped for the typist.
+    current: usize,
+    banner: String,
+    instruction: String,
+    /// The error limits and failure labels for every exercise, and for
+    /// just the next one.
+    error_max: Option<f64>,
+    next_error_max: Option<f64>,
+    on_failure: Option<String>,
+    next_on_failure: Option<String>,
+    /// The error limit of the last exercise, and where to go if it failed.
+    exercise_error_max: f64,
+    failure_label: Option<String>,
+    answer: bool,
+}
+
+/// What the typist is asked to do next.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Step {
+    /// Read a page of text.
+    Page {
+        banner: String,
+        text: String,
+    },
+    Exercise(Exercise),
+    Menu {
+        banner: String,
+        menu: Menu,
+    },
+    /// Answer yes or no.
+    Query {
+        banner: String,
+        question: String,
+    },
+    /// The lesson is over.
+    Exit,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Exercise {
+    pub banner: String,
+    pub instruction: String,
+    pub drill: Drill,
+    /// The percentage of errors above which the exercise fails, unless it
+    /// is practice.
+    pub error_max: Option<f64>,
+}
+
+impl Exercise {
+    /// Whether typing the exercise with this accuracy, between 0 and 1,
+    /// passes it.
+    pub fn passes(&self, accuracy: f64) -> bool {
+        self.error_max
+            .is_none_or(|error_max| (1.0 - accuracy) * 100.0 <= error_max + f64::EPSILON)
+    }
+}
+
+impl Interpreter {
+    pub fn new(program: Program) -> Interpreter {
+        Interpreter {
+            program,
+            ..Interpreter::default()
+        }
+    }
+
+    /// Starts the lesson at `label` rather than the top, if there is such
+    /// a label.
+    pub fn start_at(mut self, label: &str) -> Option<Interpreter> {
+        self.next = self.program.label(label)?;
+        Some(self)
+    }
+
+    /// Runs commands up to the next one the typist must respond to. A
+    /// script that jumps round in a circle without one ends there.
+    pub fn step(&mut self) -> Step {
+        let mut visited = HashSet::new();
+        loop {
+            if !visited.insert(self.next) {
+                return Step::Exit;
+            }
+            let command = match self.program.commands().get(self.next) {
+                Some(command) => command.clone(),
+                None => return Step::Exit,
+            };
+            self.current = self.next;
+            self.next += 1;
+            match command {
+                Command::Banner(banner) => self.banner = banner,
+                Command::Instruction(instruction) => self.instruction = instruction,
+                Command::ErrorMax {
+                    percent,
+                    persistent: true,
+                } => self.error_max = Some(percent),
+                Command::ErrorMax { percent, .. } => self.next_error_max = Some(percent),
+                Command::OnFailure {
+                    label,
+                    persistent: true,
+                } => self.on_failure = label,
+                Command::OnFailure { label, .. } => self.next_on_failure = label,
+                Command::Goto(label) => self.jump(&label),
+                Command::IfYes(label) if self.answer => self.jump(&label),
+                Command::IfNo(label) if !self.answer => self.jump(&label),
+                Command::IfYes(_) | Command::IfNo(_) => {}
+                Command::Exit => return Step::Exit,
+                Command::Text(text) => {
+                    return Step::Page {
+                        banner: self.banner.clone(),
+                        text,
+                    }
+                }
+                Command::Drill(drill) => {
+                    let error_max = self
+                        .next_error_max
+                        .take()
+                        .or(self.error_max)
+                        .unwrap_or(DEFAULT_ERROR_MAX);
+                    self.exercise_error_max = error_max;
+                    self.failure_label = self.next_on_failure.take().or(self.on_failure.clone());
+                    return Step::Exercise(Exercise {
+                        banner: self.banner.clone(),
+                        instruction: self.instruction.clone(),
+                        error_max: match drill.practice {
+                            true => None,
+                            false => Some(error_max),
+                        },
+                        drill,
+                    });
+                }
+                Command::Menu(menu) => {
+                    return Step::Menu {
+                        banner: self.banner.clone(),
+                        menu,
+                    }
+                }
+                Command::Query(question) => {
+                    return Step::Query {
+                        banner: self.banner.clone(),
+                        question,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Moves on from the last exercise, repeating it or going to the
+    /// failure label if it was failed.
+    pub fn finished(&mut self, passed: bool) {
+        if passed {
+            return;
+        }
+        match self.failure_label.take() {
+            Some(label) => self.jump(&label),
+            None => {
+                // repeated with the same limit
+                self.next = self.current;
+                self.next_error_max = Some(self.exercise_error_max);
+            }
+        }
+    }
+
+    /// Answers the last question.
+    pub fn answer(&mut self, yes: bool) {
+        self.answer = yes;
+    }
+
+    /// Carries on from `label`, which the parser has checked exists.
+    pub fn jump(&mut self, label: &str) {
+        if let Some(next) = self.program.label(label) {
+            self.next = next;
+        }
+    }
+}
@@ -0,0 +1,8 @@
+#[cfg(test)]
+mod tests;
+
+mod interpreter;
+mod program;
+
+pub use interpreter::{Exercise, Interpreter, Step};
+pub use program::{Command, Drill, DrillKind, Menu, Program, EXIT_LABEL};
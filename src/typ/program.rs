@@ -0,0 +1,284 @@
+use crate::text_model::{DataError, ParseError};
+use std::collections::HashMap;
+
+/// The label gtypist scripts jump to in order to end.
+pub const EXIT_LABEL: &str = "_EXIT";
+
+/// A GNU Typist lesson script, parsed from its `.typ` file.
+#[derive(Debug, Default, PartialEq)]
+pub struct Program {
+    commands: Vec<Command>,
+    /// The index of the command after each label.
+    labels: HashMap<String, usize>,
+}
+
+/// A command from a `.typ` file, each written as a letter, a colon and its
+/// argument, continued on following lines that start with ` :`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    /// `B:` sets the title shown above everything that follows.
+    Banner(String),
+    /// `T:` shows a page of text until a key is pressed.
+    Text(String),
+    /// `I:` sets the instruction shown with the next exercises.
+    Instruction(String),
+    /// `D:`, `d:`, `S:` and `s:` are typing exercises.
+    Drill(Drill),
+    /// `G:` jumps to a label.
+    Goto(String),
+    /// `Q:` asks a yes or no question.
+    Query(String),
+    /// `Y:` jumps to a label if the last question was answered yes.
+    IfYes(String),
+    /// `N:` jumps to a label if the last question was answered no.
+    IfNo(String),
+    /// `M:` offers a menu of labels to jump to.
+    Menu(Menu),
+    /// `E:` sets the highest percentage of errors the next exercise may
+    /// have, or every exercise after it if followed by `*`.
+    ErrorMax { percent: f64, persistent: bool },
+    /// `F:` sets the label to jump to when the next exercise fails, rather
+    /// than repeating it, or every exercise if followed by `*`. `NULL`
+    /// clears it.
+    OnFailure {
+        label: Option<String>,
+        persistent: bool,
+    },
+    /// `X:` ends the lesson.
+    Exit,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DrillKind {
+    /// Typed without correction, as in gtypist's drills.
+    Drill,
+    /// Typed with correction, against the clock.
+    SpeedTest,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Drill {
+    pub kind: DrillKind,
+    pub text: String,
+    /// Practice exercises, written in lower case, never fail.
+    pub practice: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Menu {
+    pub title: String,
+    /// Where to go if the menu is left without a choice.
+    pub up: Option<String>,
+    /// The label and description of each choice.
+    pub items: Vec<(String, String)>,
+}
+
+impl Program {
+    pub fn parse(source: &str) -> Result<Program, ParseError> {
+        let mut program = Program::default();
+        // each command with the offset it starts at, for checking jumps
+        let mut jumps = Vec::new();
+        for (offset, letter, lines) in statements(source)? {
+            let argument = lines.join("\n");
+            let error = |error| ParseError::new(source, offset, error);
+            let command = match letter {
+                '*' => {
+                    let label = argument.trim().to_string();
+                    if program.labels.contains_key(&label) {
+                        return Err(error(DataError::DuplicateLabel(label)));
+                    }
+                    program.labels.insert(label, program.commands.len());
+                    continue;
+                }
+                'K' => continue,
+                'B' => Command::Banner(argument.trim().to_string()),
+                'T' => Command::Text(argument),
+                'I' => Command::Instruction(argument),
+                'D' | 'd' | 'S' | 's' => {
+                    if argument.trim().is_empty() {
+                        return Err(error(DataError::BadCommand("an exercise needs some text")));
+                    }
+                    if let Some(c) = crate::text_model::unsupported_character(&argument) {
+                        let at = source[offset..].find(c).map_or(offset, |i| offset + i);
+                        return Err(ParseError::new(
+                            source,
+                            at,
+                            DataError::UnsupportedCharacter(c),
+                        ));
+                    }
+                    Command::Drill(Drill {
+                        kind: match letter.to_ascii_uppercase() {
+                            'S' => DrillKind::SpeedTest,
+                            _ => DrillKind::Drill,
+                        },
+                        text: argument,
+                        practice: letter.is_ascii_lowercase(),
+                    })
+                }
+                'G' => Command::Goto(argument.trim().to_string()),
+                'Q' => Command::Query(argument),
+                'Y' => Command::IfYes(argument.trim().to_string()),
+                'N' => Command::IfNo(argument.trim().to_string()),
+                'M' => Command::Menu(menu(&lines).ok_or_else(|| {
+                    error(DataError::BadCommand("a menu needs a title and its items"))
+                })?),
+                'E' => {
+                    let (value, persistent) = persistent(&argument);
+                    Command::ErrorMax {
+                        percent: value
+                            .trim_end_matches('%')
+                            .parse()
+                            .map_err(|_| error(DataError::BadCommand("expected a percentage")))?,
+                        persistent,
+                    }
+                }
+                'F' => {
+                    let (label, persistent) = persistent(&argument);
+                    Command::OnFailure {
+                        label: match label {
+                            "" | "NULL" => None,
+                            label => Some(label.to_string()),
+                        },
+                        persistent,
+                    }
+                }
+                'X' => Command::Exit,
+                c => return Err(error(DataError::UnknownCommand(c))),
+            };
+            jumps.extend(
+                command
+                    .labels()
+                    .into_iter()
+                    .map(|label| (label.to_string(), offset)),
+            );
+            program.commands.push(command);
+        }
+        for (label, offset) in jumps {
+            if label != EXIT_LABEL && !program.labels.contains_key(&label) {
+                return Err(ParseError::new(
+                    source,
+                    offset,
+                    DataError::UnknownLabel(label),
+                ));
+            }
+        }
+        Ok(program)
+    }
+
+    pub fn commands(&self) -> &[Command] {
+        &self.commands
+    }
+
+    /// The index of the command after `label`, if there is such a label.
+    pub fn label(&self, label: &str) -> Option<usize> {
+        match label {
+            EXIT_LABEL => Some(self.commands.len()),
+            label => self.labels.get(label).copied(),
+        }
+    }
+}
+
+impl Command {
+    /// The labels the command may jump to.
+    fn labels(&self) -> Vec<&str> {
+        match self {
+            Command::Goto(label) | Command::IfYes(label) | Command::IfNo(label) => vec![label],
+            Command::OnFailure {
+                label: Some(label), ..
+            } => vec![label],
+            Command::Menu(menu) => menu
+                .up
+                .iter()
+                .chain(menu.items.iter().map(|(label, _)| label))
+                .map(String::as_str)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// A command as written: where it starts, its letter, and the lines of
+/// its argument.
+type Statement<'a> = (usize, char, Vec<&'a str>);
+
+/// Each command in `source`.
+fn statements(source: &str) -> Result<Vec<Statement<'_>>, ParseError> {
+    let mut statements: Vec<Statement> = Vec::new();
+    let mut offset = 0;
+    for line in source.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let line = line.trim_end_matches(['\n', '\r']);
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut chars = line.chars();
+        let (letter, colon) = (chars.next(), chars.next());
+        match (letter, colon) {
+            (Some(' '), Some(':')) => match statements.last_mut() {
+                Some((_, _, lines)) => lines.push(&line[2..]),
+                None => {
+                    let error =
+                        DataError::BadCommand("a continuation line with nothing to continue");
+                    return Err(ParseError::new(source, start, error));
+                }
+            },
+            (Some(letter), Some(':')) => {
+                let argument = &line[letter.len_utf8() + 1..];
+                statements.push((start, letter, vec![argument]));
+            }
+            _ => {
+                let error = DataError::BadCommand("expected a command letter and a colon");
+                return Err(ParseError::new(source, start, error));
+            }
+        }
+    }
+    Ok(statements)
+}
+
+/// A menu from its lines: `[UP=LABEL] "Title"`, then `LABEL "Item"` for
+/// each choice.
+fn menu(lines: &[&str]) -> Option<Menu> {
+    let (first, items) = lines.split_first()?;
+    let first = first.trim();
+    let (up, title) = match first.strip_prefix("UP=") {
+        Some(rest) => {
+            let (up, title) = rest.split_once(char::is_whitespace)?;
+            (Some(up.to_string()), title)
+        }
+        None => (None, first),
+    };
+    let items = items
+        .iter()
+        .map(|item| {
+            let (label, description) = item.trim().split_once(char::is_whitespace)?;
+            Some((label.to_string(), unquote(description)))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if items.is_empty() {
+        return None;
+    }
+    Some(Menu {
+        title: unquote(title),
+        up,
+        items,
+    })
+}
+
+/// An `E:` or `F:` argument, and whether it is marked with `*` to last
+/// beyond the next exercise.
+fn persistent(argument: &str) -> (&str, bool) {
+    let argument = argument.trim();
+    match argument.strip_suffix('*') {
+        Some(value) => (value.trim_end(), true),
+        None => (argument, false),
+    }
+}
+
+fn unquote(s: &str) -> String {
+    let s = s.trim();
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(s)
+        .to_string()
+}
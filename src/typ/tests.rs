@@ -0,0 +1,189 @@
+use super::*;
+
+const LESSON: &str = "\
+# a comment
+B:Lesson one
+T:Welcome to
+ :the lesson.
+*:MENU
+M: UP=_EXIT \"Choose\"
+ :DRILL \"A drill\"
+ :SPEED \"A speed test\"
+*:DRILL
+I:Type this
+D:asdf
+ :jkl;
+d:practice
+G:MENU
+*:SPEED
+E:5%*
+S:The quick fox.
+Q:Again?
+Y:SPEED
+X:
+";
+
+fn parse(source: &str) -> Program {
+    Program::parse(source).unwrap()
+}
+
+fn error(source: &str) -> String {
+    let e = Program::parse(source).unwrap_err();
+    format!("{}:{}: {}", e.line(), e.column(), e.error())
+}
+
+fn exercise(step: Step) -> Exercise {
+    match step {
+        Step::Exercise(exercise) => exercise,
+        step => panic!("Not an exercise: {:?}", step),
+    }
+}
+
+#[test]
+fn commands_are_parsed_with_continuation_lines() {
+    let program = parse(LESSON);
+    assert_eq!(
+        program.commands()[0],
+        Command::Banner("Lesson one".to_string())
+    );
+    assert_eq!(
+        program.commands()[1],
+        Command::Text("Welcome to\nthe lesson.".to_string())
+    );
+    assert_eq!(
+        program.commands()[2],
+        Command::Menu(Menu {
+            title: "Choose".to_string(),
+            up: Some(EXIT_LABEL.to_string()),
+            items: vec![
+                ("DRILL".to_string(), "A drill".to_string()),
+                ("SPEED".to_string(), "A speed test".to_string()),
+            ],
+        })
+    );
+    assert_eq!(
+        program.commands()[4],
+        Command::Drill(Drill {
+            kind: DrillKind::Drill,
+            text: "asdf\njkl;".to_string(),
+            practice: false,
+        })
+    );
+    assert_eq!(program.label("DRILL"), Some(3));
+    assert_eq!(program.label(EXIT_LABEL), Some(program.commands().len()));
+}
+
+#[test]
+fn bad_scripts_are_located() {
+    assert_eq!(error("T:hi\nG:NOWHERE\n"), "2:1: No label 'NOWHERE'");
+    assert_eq!(error("*:A\n*:A\n"), "2:1: Label 'A' is used twice");
+    assert_eq!(error("T:hi\nZ:what\n"), "2:1: Unknown command 'Z:'");
+    assert_eq!(
+        error(" :nothing\n"),
+        "1:1: Bad command: a continuation line with nothing to continue"
+    );
+    assert_eq!(error("E:lots\n"), "1:1: Bad command: expected a percentage");
+    assert!(error("D:caf\u{e9}\n").starts_with("1:6: Unsupported character"));
+}
+
+#[test]
+fn interpreter_stops_for_the_typist() {
+    let mut interpreter = Interpreter::new(parse(LESSON));
+    assert_eq!(
+        interpreter.step(),
+        Step::Page {
+            banner: "Lesson one".to_string(),
+            text: "Welcome to\nthe lesson.".to_string(),
+        }
+    );
+    assert!(matches!(interpreter.step(), Step::Menu { .. }));
+    interpreter.jump("DRILL");
+    let drill = exercise(interpreter.step());
+    assert_eq!(drill.instruction, "Type this");
+    assert_eq!(drill.error_max, Some(3.0));
+    interpreter.finished(true);
+    let practice = exercise(interpreter.step());
+    assert_eq!(practice.error_max, None);
+    assert!(practice.passes(0.0));
+    interpreter.finished(true);
+    assert!(matches!(interpreter.step(), Step::Menu { .. }));
+    interpreter.jump(EXIT_LABEL);
+    assert_eq!(interpreter.step(), Step::Exit);
+}
+
+#[test]
+fn failed_exercises_are_repeated_with_the_same_limit() {
+    let mut interpreter = Interpreter::new(parse("E:10%\nD:asdf\nT:done\n"));
+    let drill = exercise(interpreter.step());
+    assert_eq!(drill.error_max, Some(10.0));
+    assert!(drill.passes(0.9));
+    assert!(!drill.passes(0.8));
+    interpreter.finished(false);
+    assert_eq!(exercise(interpreter.step()), drill);
+    interpreter.finished(true);
+    assert!(matches!(interpreter.step(), Step::Page { .. }));
+}
+
+#[test]
+fn failure_labels_last_for_one_exercise_unless_starred() {
+    let source = "\
+F:HELP
+D:one
+D:two
+F:HELP*
+D:three
+*:HELP
+T:help
+";
+    let mut interpreter = Interpreter::new(parse(source));
+    exercise(interpreter.step());
+    interpreter.finished(false);
+    assert!(matches!(interpreter.step(), Step::Page { .. }));
+
+    let mut interpreter = Interpreter::new(parse(source)).start_at("HELP").unwrap();
+    assert!(matches!(interpreter.step(), Step::Page { .. }));
+
+    let mut interpreter = Interpreter::new(parse(source));
+    exercise(interpreter.step());
+    interpreter.finished(true);
+    assert_eq!(exercise(interpreter.step()).drill.text, "two");
+    interpreter.finished(false);
+    assert_eq!(exercise(interpreter.step()).drill.text, "two");
+    interpreter.finished(true);
+    assert_eq!(exercise(interpreter.step()).drill.text, "three");
+    interpreter.finished(false);
+    assert!(matches!(interpreter.step(), Step::Page { .. }));
+}
+
+#[test]
+fn answers_choose_where_to_go() {
+    let mut interpreter = Interpreter::new(parse(LESSON)).start_at("SPEED").unwrap();
+    let speed = exercise(interpreter.step());
+    assert_eq!(speed.drill.kind, DrillKind::SpeedTest);
+    assert_eq!(speed.error_max, Some(5.0));
+    interpreter.finished(true);
+    assert!(matches!(interpreter.step(), Step::Query { .. }));
+    interpreter.answer(true);
+    assert_eq!(exercise(interpreter.step()), speed);
+    interpreter.finished(true);
+    interpreter.step();
+    interpreter.answer(false);
+    assert_eq!(interpreter.step(), Step::Exit);
+}
+
+#[test]
+fn jumping_in_circles_ends_the_lesson() {
+    let mut interpreter = Interpreter::new(parse("*:a\nG:a\n"));
+    assert_eq!(interpreter.step(), Step::Exit);
+    let mut interpreter = Interpreter::new(parse("*:a\nB:Title\nG:b\n*:b\nG:a\n"));
+    assert_eq!(interpreter.step(), Step::Exit);
+}
+
+#[test]
+fn looping_through_an_exercise_is_not_a_circle() {
+    let mut interpreter = Interpreter::new(parse("*:a\nD:asdf\nG:a\n"));
+    for _ in 0..3 {
+        exercise(interpreter.step());
+        interpreter.finished(true);
+    }
+}
@@ -0,0 +1,214 @@
+use crate::layout::{Finger, KeyPosition, Layout, HOME_ROW, ROWS, SPACE_ROW};
+use tui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    widgets::Widget,
+};
+
+/// The width of a key, including the gap after it.
+const KEY_WIDTH: u16 = 4;
+/// Where the keys of each row start, for the stagger of a real keyboard.
+const ROW_OFFSETS: [u16; 4] = [0, 6, 7, 9];
+/// Where the space bar starts, and how wide it is.
+const SPACE_BAR: (u16, u16) = (13, 27);
+/// How wide the AltGr key, right of the space bar, is.
+const ALTGR_WIDTH: u16 = 5;
+
+/// A keyboard drawn as its layout, with the key needed for a character
+/// highlighted, along with the shift or AltGr key if it needs one.
+pub struct Keyboard<'a> {
+    layout: &'a Layout,
+    highlight: Option<KeyPosition>,
+}
+
+impl<'a> Keyboard<'a> {
+    pub fn new(layout: &'a Layout) -> Keyboard<'a> {
+        Keyboard {
+            layout,
+            highlight: None,
+        }
+    }
+
+    /// Highlights the keys that type `c`.
+    pub fn highlight(mut self, c: Option<char>) -> Keyboard<'a> {
+        self.highlight = c.and_then(|c| self.layout.locate(c));
+        self
+    }
+
+    /// The height needed to show every row.
+    pub fn height() -> u16 {
+        ROWS as u16
+    }
+
+    fn offset(&self, row: usize) -> u16 {
+        match row {
+            3 if self.layout.iso => ROW_OFFSETS[3] - KEY_WIDTH,
+            row => ROW_OFFSETS[row],
+        }
+    }
+
+    fn width(&self) -> u16 {
+        self.layout
+            .rows
+            .iter()
+            .enumerate()
+            // the keys and the enter or shift key after them
+            .map(|(row, keys)| self.offset(row) + (keys.len() as u16 + 1) * KEY_WIDTH)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn is_highlighted(&self, row: usize, column: usize) -> bool {
+        self.highlight
+            .is_some_and(|p| p.row == row && (p.column == column || row == SPACE_ROW))
+    }
+}
+
+impl<'a> Widget for Keyboard<'a> {
+    fn render(self, area: Rect, buf: &mut tui::buffer::Buffer) {
+        let left = area.x + area.width.saturating_sub(self.width()) / 2;
+        let right = area.x + area.width;
+        let mut key = |x: u16, row: usize, label: &str, width: u16, highlight: bool| {
+            if row as u16 >= area.height || x >= right {
+                return;
+            }
+            let style = match highlight {
+                true => Style::default().add_modifier(Modifier::REVERSED),
+                false => Style::default(),
+            };
+            let label = format!("{:^width$}", label, width = width as usize);
+            let room = (right - x) as usize;
+            buf.set_stringn(x, area.y + row as u16, label, room, style);
+        };
+
+        let shift = self.highlight.and_then(|p| p.shift_finger());
+        for (row, keys) in self.layout.rows.iter().enumerate() {
+            let start = left + self.offset(row);
+            for (column, k) in keys.iter().enumerate() {
+                let label = k.unshifted.or(k.shifted).unwrap_or(' ').to_string();
+                let x = start + column as u16 * KEY_WIDTH;
+                key(
+                    x,
+                    row,
+                    &label,
+                    KEY_WIDTH - 1,
+                    self.is_highlighted(row, column),
+                );
+            }
+            let end = start + keys.len() as u16 * KEY_WIDTH;
+            match row {
+                1 => key(left, row, "⇥", ROW_OFFSETS[1] - 1, false),
+                HOME_ROW => {
+                    let enter = self.is_highlighted(row, keys.len());
+                    key(end, row, "↵", KEY_WIDTH - 1, enter);
+                }
+                3 => {
+                    let left_shift = shift == Some(Finger::LeftPinky);
+                    key(left, row, "⇧", self.offset(3) - 1, left_shift);
+                    key(
+                        end,
+                        row,
+                        "⇧",
+                        KEY_WIDTH - 1,
+                        shift == Some(Finger::RightPinky),
+                    );
+                }
+                _ => {}
+            }
+        }
+        let (x, width) = SPACE_BAR;
+        key(
+            left + x,
+            SPACE_ROW,
+            "space",
+            width,
+            self.is_highlighted(SPACE_ROW, 0),
+        );
+        let has_altgr = self.layout.rows.iter().flatten().any(|k| k.altgr.is_some());
+        if has_altgr {
+            let altgr = self.highlight.is_some_and(|p| p.altgr);
+            key(left + x + width + 1, SPACE_ROW, "altgr", ALTGR_WIDTH, altgr);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(c: Option<char>, width: u16) -> tui::buffer::Buffer {
+        render_layout("qwerty", c, width)
+    }
+
+    fn render_layout(name: &str, c: Option<char>, width: u16) -> tui::buffer::Buffer {
+        let layout = Layout::built_in(name).unwrap();
+        let rect = Rect {
+            width,
+            height: Keyboard::height(),
+            ..Default::default()
+        };
+        let mut buffer = tui::buffer::Buffer::empty(rect);
+        Keyboard::new(&layout)
+            .highlight(c)
+            .render(rect, &mut buffer);
+        buffer
+    }
+
+    /// The characters drawn reversed, row by row.
+    fn highlighted(buffer: &tui::buffer::Buffer) -> String {
+        buffer
+            .content
+            .iter()
+            .filter(|c| c.modifier.contains(Modifier::REVERSED))
+            .map(|c| c.symbol.trim())
+            .collect()
+    }
+
+    #[test]
+    fn keyboard_draws_the_layout() {
+        let buffer = render(None, 60);
+        let row = |y: usize| {
+            buffer.content[y * 60..(y + 1) * 60]
+                .iter()
+                .map(|c| c.symbol.clone())
+                .collect::<String>()
+        };
+        assert!(row(0).contains(" 1   2   3 "));
+        assert!(row(2).contains(" a   s   d "));
+        assert!(row(4).contains("space"));
+        assert_eq!(highlighted(&buffer), "");
+    }
+
+    #[test]
+    fn keyboard_highlights_the_key_and_shift() {
+        assert_eq!(highlighted(&render(Some('f'), 60)), "f");
+        // the shift key is held by the other hand, and drawn on the row below
+        assert_eq!(highlighted(&render(Some('F'), 60)), "f⇧");
+        assert_eq!(highlighted(&render(Some('J'), 60)), "j⇧");
+        assert_eq!(highlighted(&render(Some(' '), 60)), "space");
+        assert_eq!(highlighted(&render(Some('\n'), 60)), "↵");
+    }
+
+    #[test]
+    fn keyboard_highlights_altgr() {
+        assert!(!row_text(&render(None, 60), 4).contains("altgr"));
+        let buffer = render_layout("azerty", Some('@'), 60);
+        assert!(row_text(&buffer, 4).contains("altgr"));
+        assert_eq!(highlighted(&buffer), "àaltgr");
+    }
+
+    fn row_text(buffer: &tui::buffer::Buffer, y: usize) -> String {
+        let width = buffer.area.width as usize;
+        buffer.content[y * width..(y + 1) * width]
+            .iter()
+            .map(|c| c.symbol.clone())
+            .collect()
+    }
+
+    #[test]
+    fn keyboard_clips_to_narrow_areas() {
+        let buffer = render(Some('p'), 20);
+        assert_eq!(buffer.area.width, 20);
+        assert_eq!(highlighted(&buffer), "");
+    }
+}
@@ -0,0 +1,110 @@
+use tui::{
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{Block, BorderType, Borders, Widget},
+};
+
+/// One racer's progress through the text.
+pub struct Lane {
+    pub name: String,
+    /// Proportion of the text covered, between 0 and 1.
+    pub progress: f64,
+    pub highlight: bool,
+}
+
+/// A progress bar per racer, drawn one above the other.
+pub struct Lanes {
+    lanes: Vec<Lane>,
+}
+
+impl Lanes {
+    pub fn new(lanes: Vec<Lane>) -> Lanes {
+        Lanes { lanes }
+    }
+
+    /// The height needed to show every lane.
+    pub fn height(&self) -> u16 {
+        self.lanes.len() as u16 + 2
+    }
+}
+
+impl Widget for Lanes {
+    fn render(self, area: Rect, buf: &mut tui::buffer::Buffer) {
+        let borders = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded);
+        let inner = borders.inner(area);
+        borders.render(area, buf);
+
+        let name_width = self
+            .lanes
+            .iter()
+            .map(|l| l.name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .min(inner.width as usize / 3);
+        let track_width = (inner.width as usize).saturating_sub(name_width + 1);
+        for (row, lane) in self.lanes.iter().take(inner.height as usize).enumerate() {
+            let style = match lane.highlight {
+                true => Style::default().fg(Color::White),
+                false => Style::default().fg(Color::DarkGray),
+            };
+            let covered = (lane.progress.clamp(0.0, 1.0) * track_width as f64).round() as usize;
+            let line = format!(
+                "{:width$.width$} {}{}",
+                lane.name,
+                "━".repeat(covered),
+                "─".repeat(track_width - covered),
+                width = name_width,
+            );
+            buf.set_stringn(
+                inner.x,
+                inner.y + row as u16,
+                line,
+                inner.width as usize,
+                style,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lanes_draw_progress_bars() {
+        let lanes = Lanes::new(vec![
+            Lane {
+                name: "me".to_string(),
+                progress: 0.5,
+                highlight: true,
+            },
+            Lane {
+                name: "bot".to_string(),
+                progress: 1.0,
+                highlight: false,
+            },
+        ]);
+        assert_eq!(lanes.height(), 4);
+
+        let rect = Rect {
+            width: 14,
+            height: 4,
+            ..Default::default()
+        };
+        let mut buffer = tui::buffer::Buffer::empty(rect);
+        lanes.render(rect, &mut buffer);
+
+        let row = |y: usize| {
+            buffer.content[y * 14 + 1..y * 14 + 13]
+                .iter()
+                .map(|c| c.symbol.clone())
+                .collect::<String>()
+        };
+        assert_eq!(row(1), "me  ━━━━────");
+        assert_eq!(row(2), "bot ━━━━━━━━");
+        assert_eq!(buffer.content[15].fg, Color::White);
+        assert_eq!(buffer.content[29].fg, Color::DarkGray);
+    }
+}
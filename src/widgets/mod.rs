@@ -1,3 +1,11 @@
+mod keyboard;
+mod lanes;
+mod popup;
 mod text_area;
+mod too_small;
 
-pub use text_area::TextArea;
+pub use keyboard::Keyboard;
+pub use lanes::{Lane, Lanes};
+pub use popup::Popup;
+pub use text_area::{Presentation, TextArea};
+pub use too_small::TooSmall;
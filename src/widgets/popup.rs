@@ -0,0 +1,38 @@
+use tui::{
+    layout::{Alignment, Rect},
+    text::Text,
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Widget},
+};
+
+/// A small framed message drawn centred over whatever is beneath it.
+pub struct Popup<'a> {
+    text: Text<'a>,
+}
+
+impl<'a> Popup<'a> {
+    pub fn new<T: Into<Text<'a>>>(text: T) -> Popup<'a> {
+        Popup { text: text.into() }
+    }
+}
+
+impl Widget for Popup<'_> {
+    fn render(self, area: Rect, buf: &mut tui::buffer::Buffer) {
+        let width = (self.text.width() as u16 + 4).min(area.width);
+        let height = (self.text.height() as u16 + 2).min(area.height);
+        let popup_area = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+        Clear.render(popup_area, buf);
+        Paragraph::new(self.text)
+            .alignment(Alignment::Center)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded),
+            )
+            .render(popup_area, buf);
+    }
+}
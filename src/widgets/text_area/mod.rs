@@ -1,5 +1,7 @@
 mod reflow;
+#[allow(clippy::module_inception)]
 mod text_area;
 mod styled_char;
 
+pub use styled_char::Presentation;
 pub use text_area::TextArea;
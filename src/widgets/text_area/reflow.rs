@@ -1,5 +1,5 @@
 pub fn reflow<C, P, F>(
-    chars: &Vec<C>,
+    chars: &[C],
     line_width: usize, 
     is_separator: P, 
     is_newline: F,
@@ -32,7 +32,7 @@ where
             },
             None => {
                 append_word(&mut ret, &mut line, &mut word, line_width);
-                if line.len() != 0 {
+                if !line.is_empty() {
                     ret.push(std::mem::take(&mut line));
                 }
                 break;
@@ -42,6 +42,18 @@ where
     ret
 }
 
+/// Finds the line and column of the `index`th character in reflowed `lines`.
+pub fn locate<C>(lines: &[Vec<C>], index: usize) -> Option<(usize, usize)> {
+    let mut start = 0;
+    for (row, line) in lines.iter().enumerate() {
+        if index < start + line.len() {
+            return Some((row, index - start));
+        }
+        start += line.len();
+    }
+    None
+}
+
 fn append_word<C>(
     lines: &mut Vec<Vec<C>>,
     line: &mut Vec<C>,
@@ -55,7 +67,7 @@ where C: std::clone::Clone + std::cmp::PartialEq {
             line.append(word);
             break;
         } else {
-            assert!(line.len() != 0);
+            assert!(!line.is_empty());
             // start a new line
             lines.push(std::mem::take(line));
         }
@@ -149,4 +161,22 @@ mod tests {
             vec![char_vec("r"), char_vec("e"), char_vec("u")]
         )
     }
+
+    #[test]
+    fn locate_first_character() {
+        let lines = reflow(&char_vec("reu reu"), 4, |&c| c == ' ', |&c| c == '\n');
+        assert_eq!(locate(&lines, 0), Some((0, 0)));
+    }
+
+    #[test]
+    fn locate_on_wrapped_line() {
+        let lines = reflow(&char_vec("reu reu"), 4, |&c| c == ' ', |&c| c == '\n');
+        assert_eq!(locate(&lines, 5), Some((1, 1)));
+    }
+
+    #[test]
+    fn locate_past_the_end() {
+        let lines = reflow(&char_vec("reu"), 4, |&c| c == ' ', |&c| c == '\n');
+        assert_eq!(locate(&lines, 3), None);
+    }
 }
\ No newline at end of file
@@ -1,6 +1,15 @@
 use crate::text_model::{ Character, CharacterStatus, };
 use tui::style::{ Color, Style, };
 
+/// How typed characters are coloured.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Presentation {
+    /// Typed characters are coloured by whether they are correct.
+    Feedback,
+    /// Typed characters are all drawn alike, so mistakes are hidden.
+    Blind,
+}
+
 #[derive(Clone, PartialEq)]
 pub struct StyledChar {
     pub style: Style,
@@ -8,6 +17,46 @@ pub struct StyledChar {
 }
 
 impl StyledChar {
+    pub fn styled(c: &Character, presentation: Presentation) -> StyledChar {
+        match presentation {
+            Presentation::Feedback => StyledChar::from(c),
+            Presentation::Blind => StyledChar {
+                style: Style {
+                    fg: match c.status() {
+                        CharacterStatus::Untyped => Some(Color::DarkGray),
+                        _ => Some(Color::White),
+                    },
+                    ..Style::default()
+                },
+                c: c.value(),
+            },
+        }
+    }
+
+    pub fn ghost(self, is_ghost: bool) -> StyledChar {
+        if !is_ghost {
+            return self;
+        }
+        self.background(Color::Blue)
+    }
+
+    pub fn pacer(self, is_pacer: bool) -> StyledChar {
+        if !is_pacer {
+            return self;
+        }
+        self.background(Color::Magenta)
+    }
+
+    fn background(self, color: Color) -> StyledChar {
+        StyledChar {
+            style: Style {
+                bg: Some(color),
+                ..self.style
+            },
+            ..self
+        }
+    }
+
     pub fn cursor(self, is_cursor: bool) -> StyledChar {
         if !is_cursor {
             return self;
@@ -1,5 +1,5 @@
 use crate::text_model;
-use super::styled_char::StyledChar;
+use super::styled_char::{Presentation, StyledChar};
 use tui::{
     layout::Rect,
     widgets::{Block, BorderType, Borders, },
@@ -7,25 +7,68 @@ use tui::{
 
 pub struct TextArea {
     characters: Vec<StyledChar>,
+    cursor: usize,
+    title: Option<String>,
 }
 
 impl TextArea {
     pub fn new(text: &text_model::TextModel) -> TextArea {
+        TextArea::with_presentation(text, Presentation::Feedback)
+    }
+
+    pub fn with_presentation(
+        text: &text_model::TextModel,
+        presentation: Presentation,
+    ) -> TextArea {
         TextArea {
             characters: text.characters()
-                .map(|c| StyledChar::from(c))
+                .map(|c| StyledChar::styled(c, presentation))
                 .enumerate()
                 .map(|(i, c)| c.cursor(i == text.cursor()))
                 .collect::<Vec<_>>(),
+            cursor: text.cursor(),
+            title: None,
+        }
+    }
+
+    /// Shows `title` on the top border.
+    pub fn title(mut self, title: &str) -> TextArea {
+        self.title = Some(format!(" {} ", title));
+        self
+    }
+
+    /// Marks where a ghost's cursor is, unless it is under the real one.
+    pub fn ghost(self, position: usize) -> TextArea {
+        self.mark(position, |c| c.ghost(true))
+    }
+
+    /// Marks where the pacer is, unless it is under the real cursor.
+    pub fn pacer(self, position: usize) -> TextArea {
+        self.mark(position, |c| c.pacer(true))
+    }
+
+    fn mark<F: Fn(StyledChar) -> StyledChar>(mut self, position: usize, f: F) -> TextArea {
+        if position != self.cursor {
+            if let Some(c) = self.characters.get_mut(position) {
+                *c = f(c.clone());
+            }
         }
+        self
     }
 }
 
-impl<'a> tui::widgets::Widget for TextArea {
+impl tui::widgets::Widget for TextArea {
     fn render(self, area: Rect, buf: &mut tui::buffer::Buffer) {
-        let borders = Block::default()
+        // too small to fit anything inside the borders
+        if area.width < 3 || area.height < 3 {
+            return;
+        }
+        let mut borders = Block::default()
             .borders(Borders::ALL)
             .border_type(BorderType::Rounded);
+        if let Some(title) = self.title {
+            borders = borders.title(title);
+        }
         let inner = borders.inner(area);
         borders.render(area, buf);
         
@@ -36,23 +79,30 @@ impl<'a> tui::widgets::Widget for TextArea {
             |sc| sc.c == '\n',
         );
         
-        let mut lines_iter = lines.iter();
+        // scroll so that the cursor's line is visible
+        let cursor = self.cursor.min(self.characters.len().saturating_sub(1));
+        let first_line = super::reflow::locate(&lines, cursor)
+            .map_or(0, |(row, _)| (row + 1).saturating_sub(inner.height.into()));
+
+        let mut lines_iter = lines.iter().skip(first_line);
         for j in 0..inner.height {
             let line = lines_iter.next();
             for i in 0..inner.width {
-                match line {
-                    Some(l) => {
-                        if (i as usize) < l.len() {
-                            let styled_char: &StyledChar = &l[i as usize];
-                            buf.set_string(
-                                inner.x + i, 
-                                inner.y + j, 
-                                styled_char.c.to_string(), 
-                                styled_char.style)
-                        }
-                    },
-                    None => {},
-                } 
+                if let Some(l) = line {
+                    if (i as usize) < l.len() {
+                        let styled_char: &StyledChar = &l[i as usize];
+                        // show where to press enter
+                        let c = match styled_char.c {
+                            '\n' => '↵',
+                            c => c,
+                        };
+                        buf.set_string(
+                            inner.x + i, 
+                            inner.y + j, 
+                            c.to_string(), 
+                            styled_char.style)
+                    }
+                }
             }
         }
         
@@ -60,6 +110,7 @@ impl<'a> tui::widgets::Widget for TextArea {
 }
 
 #[cfg(test)]
+#[allow(clippy::clone_on_copy)]
 mod tests {
     use super::*;
     use tui::widgets::Widget;
@@ -74,6 +125,17 @@ mod tests {
         text_model::TextModel::from_string(file_str).unwrap()
     }
 
+    fn render(paragraph: TextArea) -> tui::buffer::Buffer {
+        let rect = tui::layout::Rect {
+            width: 4,
+            height: 4,
+            ..Default::default()
+        };
+        let mut buffer = tui::buffer::Buffer::empty(rect);
+        paragraph.render(rect, &mut buffer);
+        buffer
+    }
+
     #[test]
     fn paragraph_from_empty_text_renders_empty_framed_block() {
         let text = empty_text_model();
@@ -249,4 +311,77 @@ mod tests {
         assert_eq!(buffer.content[9].fg, tui::style::Color::White);
         assert_eq!(buffer.content[10].fg, tui::style::Color::White);
     }
+
+    #[test]
+    fn blind_text_hides_mistakes() {
+        let mut text = make_text_model();
+        text.type_character('x');
+        text.type_character('x');
+        let buffer = render(TextArea::with_presentation(&text, Presentation::Blind));
+
+        assert_eq!(buffer.content[5].fg, tui::style::Color::White);
+        assert_eq!(buffer.content[6].fg, tui::style::Color::White);
+        assert_eq!(buffer.content[6].bg, tui::style::Color::Reset);
+        assert_eq!(buffer.content[9].fg, tui::style::Color::DarkGray);
+    }
+
+    #[test]
+    fn ghost_rendered_blue_bg() {
+        let text = make_text_model();
+        let buffer = render(TextArea::new(&text).ghost(2));
+
+        assert_eq!(buffer.content[9].bg, tui::style::Color::Blue);
+    }
+
+    #[test]
+    fn cursor_drawn_over_ghost() {
+        let text = make_text_model();
+        let buffer = render(TextArea::new(&text).ghost(0));
+
+        assert_eq!(buffer.content[5].bg, tui::style::Color::White);
+    }
+
+    #[test]
+    fn pacer_rendered_magenta_bg() {
+        let text = make_text_model();
+        let buffer = render(TextArea::new(&text).pacer(3));
+
+        assert_eq!(buffer.content[10].bg, tui::style::Color::Magenta);
+    }
+
+    #[test]
+    fn area_too_small_for_borders_renders_nothing() {
+        let text = make_text_model();
+        let paragraph = TextArea::new(&text);
+
+        let rect = tui::layout::Rect {
+            width: 2,
+            height: 4,
+            ..Default::default()
+        };
+        let mut buffer = tui::buffer::Buffer::empty(rect);
+        paragraph.render(rect, &mut buffer);
+
+        assert_eq!(buffer, tui::buffer::Buffer::empty(rect));
+    }
+
+    #[test]
+    fn text_scrolls_to_keep_cursor_visible() {
+        let mut text = make_text_model();
+        text.type_character('I');
+        text.type_character(' ');
+        text.type_character('a');
+        let paragraph = TextArea::new(&text);
+
+        let rect = tui::layout::Rect {
+            width: 4,
+            height: 3,
+            ..Default::default()
+        };
+        let mut buffer = tui::buffer::Buffer::empty(rect);
+        paragraph.render(rect, &mut buffer);
+
+        assert_eq!(buffer.content[5].symbol, "a".to_string());
+        assert_eq!(buffer.content[6].symbol, "m".to_string());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,54 @@
+use tui::{
+    layout::{Alignment, Rect},
+    widgets::{Paragraph, Widget, Wrap},
+};
+
+/// Shown in place of a view when the terminal is too small to draw it.
+pub struct TooSmall {
+    width: u16,
+    height: u16,
+}
+
+impl TooSmall {
+    /// `width` and `height` are the smallest size the view can be drawn at.
+    pub fn new(width: u16, height: u16) -> TooSmall {
+        TooSmall { width, height }
+    }
+}
+
+impl Widget for TooSmall {
+    fn render(self, area: Rect, buf: &mut tui::buffer::Buffer) {
+        let message = format!(
+            "Terminal too small: {}x{}, need {}x{}",
+            area.width, area.height, self.width, self.height
+        );
+        Paragraph::new(message)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn says_how_big_the_terminal_needs_to_be() {
+        let rect = Rect {
+            width: 12,
+            height: 4,
+            ..Default::default()
+        };
+        let mut buffer = tui::buffer::Buffer::empty(rect);
+        TooSmall::new(20, 6).render(rect, &mut buffer);
+
+        let text = buffer
+            .content
+            .iter()
+            .map(|cell| cell.symbol.as_str())
+            .collect::<String>();
+        assert!(text.contains("12x4"));
+        assert!(text.contains("20x6"));
+    }
+}